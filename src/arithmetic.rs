@@ -0,0 +1,71 @@
+use crate::elements::{Elements, Order};
+use crate::identities::{Identity, Zero};
+use crate::number_theory::factorize;
+use crate::ring::Ring;
+use num::BigUint;
+use std::ops::{Add, Mul};
+
+/// 繰り返し二乗法によるべき乗の計算。```Identity```と```Mul```さえあればよい。
+pub fn power<T: Copy + Mul<Output = T> + Identity>(x: T, n: u64) -> T {
+    let mut res = T::identity();
+    let mut a = x;
+    let mut m = n;
+    while m > 0 {
+        if m % 2 == 1 {
+            res = res * a;
+        }
+        a = a * a;
+        m /= 2;
+    }
+    res
+}
+
+/// 整数nを 1 + 1 + ... + 1 (n個) として型```T```の元にする。
+pub fn from_u64<T: Copy + Add<Output = T> + Zero + Identity>(n: u64) -> T {
+    let mut res = T::zero();
+    let mut a = T::identity();
+    let mut m = n;
+    while m > 0 {
+        if m % 2 == 1 {
+            res = res + a;
+        }
+        a = a + a;
+        m /= 2;
+    }
+    res
+}
+
+/// 多倍長整数nを型```T```の元にする。2^64 進法の各桁を```from_u64```で作ってHorner法でまとめるので、
+/// ```u64```に収まらない整数も扱える。
+pub fn from_biguint<T: Ring>(n: &BigUint) -> T {
+    let base = from_u64::<T>(u64::MAX) + T::identity();
+    n.iter_u64_digits()
+        .rev()
+        .fold(T::zero(), |res, digit| res * base + from_u64::<T>(digit))
+}
+
+/// 有限体の元が平方元（0を含む）かどうかを Euler の規準で判定する。
+pub fn is_square<T: Ring + Order>(x: T) -> bool {
+    x == T::zero() || power(x, (T::order() - 1) / 2) == T::identity()
+}
+
+/// 有限体の乗法群の生成元（原始元）を全探索で1つ求める。
+pub fn primitive_element<T: Ring + Order + Elements>() -> T {
+    let q = T::order();
+    let factors = factorize(q - 1);
+    for g in T::elements() {
+        if g == T::zero() {
+            continue;
+        }
+        if factors
+            .iter()
+            .all(|&(l, _)| power(g, (q - 1) / l) != T::identity())
+        {
+            return g;
+        }
+    }
+    panic!(
+        "原始元が見つかりません。位数{}の有限体ではない可能性があります。",
+        q
+    );
+}
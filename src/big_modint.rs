@@ -0,0 +1,331 @@
+use crate::characteristic::Characteristic;
+use crate::encoding::ByteEncoding;
+use crate::finite_field::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::random::{Random, Rng};
+use crate::sign::Sign;
+use crate::square_root::SquareRoot;
+use num::traits::CheckedDiv;
+use num::{BigInt, BigUint, Integer, One};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// ```BigModInt```の代表元を持つ64ビットの limb の個数。256ビットまでの法を扱える。
+pub const LIMBS: usize = 4;
+
+/// ```BigModInt```の法を与える。法は奇素数で、limb を下の桁から順に並べたもの。
+///
+/// 例えば secp256k1 の p = 2^256 - 2^32 - 977 は
+/// ```[0xfffffffefffffc2f, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff]```。
+pub trait BigModulus {
+    const MODULUS: [u64; LIMBS];
+
+    /// 法を多倍長整数にしたもの
+    fn modulus() -> BigUint {
+        from_limbs(&Self::MODULUS)
+    }
+}
+
+/// limb の列を多倍長整数にする。
+fn from_limbs(limbs: &[u64; LIMBS]) -> BigUint {
+    let digits: Vec<u32> = limbs
+        .iter()
+        .flat_map(|&l| [l as u32, (l >> 32) as u32])
+        .collect();
+    BigUint::from_slice(&digits)
+}
+
+/// 多倍長整数を limb の列にする。```LIMBS```個に収まることを想定している。
+fn to_limbs(n: &BigUint) -> [u64; LIMBS] {
+    let mut limbs = [0; LIMBS];
+    for (limb, digit) in limbs.iter_mut().zip(n.iter_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+/// 多倍長整数の法```M```で割った余り。Z / pZ の元。
+///
+/// ```ModInt```と同じ演算子とトレイトを実装しているので、```Complex```や```Polynomial```、曲線の計算に使える。
+/// 代表元は limb の配列で持つので```Copy```にでき、演算のたびに多倍長整数に直して計算する。
+/// 位数が```u64```に収まらないので、```Order```や```Elements```は実装しない。
+pub struct BigModInt<M> {
+    limbs: [u64; LIMBS],
+    modulus: PhantomData<M>,
+}
+
+// ```derive```すると```M```にも同じトレイトを要求してしまうので、代表元だけを見て手で実装する。
+impl<M> Clone for BigModInt<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for BigModInt<M> {}
+
+impl<M> PartialEq for BigModInt<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+impl<M> Eq for BigModInt<M> {}
+
+impl<M> Hash for BigModInt<M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.limbs.hash(state);
+    }
+}
+
+impl<M> fmt::Debug for BigModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BigModInt({})", from_limbs(&self.limbs))
+    }
+}
+
+impl<M: BigModulus> BigModInt<M> {
+    /// コンストラクタ。代表元が一意になるよう法で割った余りとして格納。
+    pub fn new(n: &BigUint) -> Self {
+        Self {
+            limbs: to_limbs(&(n % M::modulus())),
+            modulus: PhantomData,
+        }
+    }
+
+    /// ```u64```から作る。
+    pub fn from_u64(n: u64) -> Self {
+        Self::new(&BigUint::from(n))
+    }
+
+    /// 代表元（0 以上 法 未満）を多倍長整数にする。
+    pub fn to_biguint(&self) -> BigUint {
+        from_limbs(&self.limbs)
+    }
+
+    /// べき乗。指数も多倍長整数で与える。
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        Self::new(&self.to_biguint().modpow(exponent, &M::modulus()))
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<M: BigModulus> fmt::Display for BigModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_biguint())
+    }
+}
+
+impl<M: BigModulus> Add for BigModInt<M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(&(self.to_biguint() + rhs.to_biguint()))
+    }
+}
+
+impl<M: BigModulus> AddAssign for BigModInt<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<M: BigModulus> Sub for BigModInt<M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(&(self.to_biguint() + M::modulus() - rhs.to_biguint()))
+    }
+}
+
+impl<M: BigModulus> SubAssign for BigModInt<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<M: BigModulus> Mul for BigModInt<M> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(&(self.to_biguint() * rhs.to_biguint()))
+    }
+}
+
+impl<M: BigModulus> MulAssign for BigModInt<M> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// 逆元を掛ける。逆元が存在しない元（0を含む）で割るとpanicする。
+impl<M: BigModulus> Div for BigModInt<M> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs
+            .inverse()
+            .expect("逆元が存在しない元で割ることはできません。")
+    }
+}
+
+impl<M: BigModulus> DivAssign for BigModInt<M> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+/// 割り算。逆元が存在しない元（0を含む）で割る場合は```None```を返す。
+impl<M: BigModulus> CheckedDiv for BigModInt<M> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        Some(*self * v.inverse()?)
+    }
+}
+
+/// unary negation の実装
+impl<M: BigModulus> Neg for BigModInt<M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl<M: BigModulus> Zero for BigModInt<M> {
+    fn zero() -> Self {
+        Self {
+            limbs: [0; LIMBS],
+            modulus: PhantomData,
+        }
+    }
+}
+
+impl<M: BigModulus> Identity for BigModInt<M> {
+    fn identity() -> Self {
+        Self::from_u64(1)
+    }
+}
+
+/// 拡張ユークリッドの互除法による逆元。
+impl<M: BigModulus> Inverse for BigModInt<M> {
+    fn inverse(self) -> Option<Self> {
+        let m = BigInt::from(M::modulus());
+        let e = BigInt::from(self.to_biguint()).extended_gcd(&m);
+        if !e.gcd.is_one() {
+            return None;
+        }
+        Some(Self::new(&e.x.mod_floor(&m).to_biguint().unwrap()))
+    }
+}
+
+impl<M: BigModulus> Characteristic for BigModInt<M> {
+    fn characteristic() -> BigUint {
+        M::modulus()
+    }
+}
+
+/// 法が4で割って3余るときは x^((p + 1)/4) 、そうでないときはTonelli–Shanks法による平方根。
+impl<M: BigModulus> SquareRoot for BigModInt<M> {
+    fn square_root(self) -> Option<Self> {
+        let p = M::modulus();
+        let one = BigUint::one();
+        if self == Self::zero() {
+            return Some(self);
+        }
+        if self.pow(&((&p - &one) >> 1)) != Self::identity() {
+            return None;
+        }
+        if (&p % 4u32) == BigUint::from(3u32) {
+            return Some(self.pow(&((&p + &one) >> 2)));
+        }
+        // p - 1 = q 2^s (qは奇数)
+        let s = (&p - &one).trailing_zeros().unwrap();
+        let q = (&p - &one) >> s;
+        let minus_one = -Self::identity();
+        let z = (2..)
+            .map(Self::from_u64)
+            .find(|z| z.pow(&((&p - &one) >> 1)) == minus_one)?;
+        let mut m = s;
+        let mut c = z.pow(&q);
+        let mut t = self.pow(&q);
+        let mut r = self.pow(&((&q + &one) >> 1));
+        while t != Self::identity() {
+            let mut i = 0;
+            let mut t2 = t;
+            while t2 != Self::identity() {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            if i == m {
+                return None;
+            }
+            let b = c.pow(&(BigUint::one() << (m - i - 1)));
+            m = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+        Some(r)
+    }
+}
+
+/// 代表元が奇数なら```true```。
+impl<M: BigModulus> Sign for BigModInt<M> {
+    fn sign(&self) -> bool {
+        self.limbs[0] % 2 == 1
+    }
+}
+
+/// ```ModInt```と同じく、代表元を p - 1 が入る最小のバイト数のビッグエンディアンで表す。
+impl<M: BigModulus> ByteEncoding for BigModInt<M> {
+    fn byte_length() -> usize {
+        (M::modulus() - 1u32).bits().div_ceil(8).max(1) as usize
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let bytes = self.to_biguint().to_bytes_be();
+        let mut padded = vec![0; Self::byte_length() - bytes.len()];
+        padded.extend(bytes);
+        padded
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::byte_length() {
+            return None;
+        }
+        let n = BigUint::from_bytes_be(bytes);
+        if n >= M::modulus() {
+            return None;
+        }
+        Some(Self::new(&n))
+    }
+}
+
+impl<M: BigModulus> FiniteField for BigModInt<M> {
+    fn degree() -> u32 {
+        1
+    }
+
+    fn frobenius(self) -> Self {
+        self
+    }
+}
+
+/// 法のビット長だけ乱数をとり、法以上なら取り直す。
+impl<M: BigModulus> Random for BigModInt<M> {
+    fn random(rng: &mut Rng) -> Self {
+        let p = M::modulus();
+        let bits = p.bits();
+        loop {
+            let mut digits: Vec<u64> = (0..bits.div_ceil(64)).map(|_| rng.next_u64()).collect();
+            if !bits.is_multiple_of(64) {
+                let last = digits.len() - 1;
+                digits[last] &= (1 << (bits % 64)) - 1;
+            }
+            let mut limbs = [0; LIMBS];
+            limbs[..digits.len()].copy_from_slice(&digits);
+            let n = from_limbs(&limbs);
+            if n < p {
+                return Self::new(&n);
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
-/// 標数を与える。
+use num::BigUint;
+
+/// 標数を与える。256ビットの素数なども扱えるように多倍長整数で返す。
 pub trait Characteristic {
-    fn characteristic() -> u64;
+    fn characteristic() -> BigUint;
 }
@@ -0,0 +1,124 @@
+use crate::elements::{Elements, Order};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::identities::{Identity, Zero};
+use std::fmt;
+
+/// 判別式が0でない (a, b) を全て走り、楕円曲線 y^2 = x^3 + ax + b を順に返すイテレータ。
+pub struct EllipticCurves<T> {
+    elements: Vec<T>,
+    index: usize,
+}
+
+impl<T: Elements> EllipticCurves<T> {
+    /// コンストラクタ。
+    pub fn new() -> Self {
+        Self {
+            elements: T::elements(),
+            index: 0,
+        }
+    }
+}
+
+impl<T: Elements> Default for EllipticCurves<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Field> Iterator for EllipticCurves<T> {
+    type Item = EllipticCurve<T>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let q = self.elements.len();
+        while self.index < q * q {
+            let a = self.elements[self.index / q];
+            let b = self.elements[self.index % q];
+            self.index += 1;
+            let curve = EllipticCurve::new(a, b);
+            if !curve.is_singular() {
+                return Some(curve);
+            }
+        }
+        None
+    }
+}
+
+/// 楕円曲線の同型類。
+#[derive(Debug, Clone)]
+pub struct IsomorphismClass<T> {
+    representative: EllipticCurve<T>,
+    j_invariant: T,
+    curves: Vec<EllipticCurve<T>>,
+    point_count: u64,
+    group_structure: (u64, u64),
+}
+
+impl<T: Copy> IsomorphismClass<T> {
+    /// 代表元
+    pub fn representative(&self) -> EllipticCurve<T> {
+        self.representative
+    }
+
+    /// j不変量
+    pub fn j_invariant(&self) -> T {
+        self.j_invariant
+    }
+
+    /// この同型類に属する曲線 y^2 = x^3 + ax + b を全て並べたもの。
+    pub fn curves(&self) -> &Vec<EllipticCurve<T>> {
+        &self.curves
+    }
+
+    /// 有理点の個数
+    pub fn point_count(&self) -> u64 {
+        self.point_count
+    }
+
+    /// 有理点のなす群の構造 Z/n1 × Z/n2 の (n1, n2)
+    pub fn group_structure(&self) -> (u64, u64) {
+        self.group_structure
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<T: fmt::Display + Copy + Zero + Identity + Eq> fmt::Display for IsomorphismClass<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "j = {}, {}, 曲線の個数 {}, 有理点の個数 {}, 群の構造 Z/{} × Z/{}",
+            self.j_invariant,
+            self.representative,
+            self.curves.len(),
+            self.point_count,
+            self.group_structure.0,
+            self.group_structure.1
+        )
+    }
+}
+
+/// 全ての楕円曲線を同型類に分ける。
+/// j不変量ごとに代表元のツイストを並べ、各曲線をそれと同型なツイストの類に入れる。
+pub fn isomorphism_classes<T: Field + Order + Elements>() -> Vec<IsomorphismClass<T>> {
+    let mut classes: Vec<IsomorphismClass<T>> = Vec::new();
+    for curve in EllipticCurves::<T>::new() {
+        let j = curve.j_invariant().unwrap();
+        if !classes.iter().any(|class| class.j_invariant == j) {
+            for twist in curve.twists() {
+                let (n1, n2) = twist.group_structure();
+                classes.push(IsomorphismClass {
+                    representative: twist,
+                    j_invariant: j,
+                    curves: Vec::new(),
+                    point_count: n1 * n2,
+                    group_structure: (n1, n2),
+                });
+            }
+        }
+        let class = classes
+            .iter_mut()
+            .find(|class| class.j_invariant == j && class.representative.is_isomorphic(&curve))
+            .unwrap();
+        class.curves.push(curve);
+    }
+    classes
+}
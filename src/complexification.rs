@@ -1,8 +1,19 @@
 use crate::characteristic::Characteristic;
+use crate::elements::{Elements, Order};
+use crate::encoding::ByteEncoding;
+use crate::field::Field;
+use crate::finite_field::FiniteField;
 use crate::identities::{Identity, Zero};
+use crate::integer::{FromInteger, ToInteger};
 use crate::inverse::Inverse;
+use crate::random::{Random, Rng};
+use crate::ring::Ring;
+use crate::sign::Sign;
+use crate::square_root::SquareRoot;
+use num::traits::{CheckedDiv, Inv, Pow};
+use num::{BigUint, Num};
 use std::fmt;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
 
 /// R\[x\]/(x^2 + 1) の元。
 /// ここでRは型```T```の対象のなす環。
@@ -94,6 +105,38 @@ impl<T: Copy + Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Eq> MulAssi
     }
 }
 
+/// 逆元を掛ける。逆元が存在しない元（0を含む）で割るとpanicする。
+impl<T: Field> Div for Complex<T> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs
+            .inverse()
+            .expect("逆元が存在しない元で割ることはできません。")
+    }
+}
+
+impl<T: Field> DivAssign for Complex<T> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+/// 割り算。逆元が存在しない元（0を含む）で割る場合は```None```を返す。
+impl<T: Field> CheckedDiv for Complex<T> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        Some(*self * v.inverse()?)
+    }
+}
+
+/// 割り切れるので余りは常に0。```Div```と同じく、逆元が存在しない元で割るとpanicする。
+impl<T: Field> Rem for Complex<T> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self {
+        self - self / rhs * rhs
+    }
+}
+
 impl<T: Neg<Output = T>> Neg for Complex<T> {
     type Output = Self;
     fn neg(self) -> Self {
@@ -122,9 +165,7 @@ impl<T: Copy + Zero + Identity> Identity for Complex<T> {
     }
 }
 
-impl<T: Copy + Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Eq + Zero + Identity>
-    Complex<T>
-{
+impl<T: Ring> Complex<T> {
     /// 繰り返し二乗法によるべき乗の計算
     pub fn modpow(&self, n: u64) -> Self {
         let mut res_r = T::identity();
@@ -156,27 +197,236 @@ impl<T: Copy + Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Eq + Zero +
 }
 
 impl<T: Characteristic> Characteristic for Complex<T> {
-    fn characteristic() -> u64 {
+    fn characteristic() -> BigUint {
         T::characteristic()
     }
 }
 
-impl<
-        T: Characteristic
-            + Copy
-            + Add<Output = T>
-            + Mul<Output = T>
-            + Sub<Output = T>
-            + Eq
-            + Zero
-            + Identity,
-    > Inverse for Complex<T>
-{
+/// (a + bi)^(-1) = (a - bi) / (a^2 + b^2) 。ノルム a^2 + b^2 の逆元を```T```で1回計算するだけで済む。
+/// ノルムが可逆でない場合（0の場合を含む）は```None```を返す。
+impl<T: Field> Inverse for Complex<T> {
     fn inverse(self) -> Option<Complex<T>> {
-        if self.real == T::zero() && self.imaginary == T::zero() {
-            None
+        let norm = self.real * self.real + self.imaginary * self.imaginary;
+        let n = norm.inverse()?;
+        Some(Complex::new(self.real * n, -self.imaginary * n))
+    }
+}
+
+/// R\[x\]/(x^2 + 1) が体になる場合（すなわち F_p^2）の位数。
+/// p^2 が```u64```に収まらない（p > 2^32 の）場合はパニックする。そのときは```FiniteField::cardinality```を使う。
+impl<T: Order> Order for Complex<T> {
+    fn order() -> u64 {
+        T::order()
+            .checked_mul(T::order())
+            .expect("F_p^2 の位数が u64 に収まりません。")
+    }
+}
+
+impl<T: Elements + Copy> Elements for Complex<T> {
+    fn elements() -> Vec<Self> {
+        let v = T::elements();
+        let mut res: Vec<Self> = Vec::new();
+        for &r in &v {
+            for &i in &v {
+                res.push(Complex::new(r, i));
+            }
+        }
+        res
+    }
+}
+
+/// a + bi を a + bq (q は```T```の位数) に対応させる。
+/// F_p^2 の元を p 進法の2桁の整数とみなすことに相当する。
+/// ```Order```と同じく、結果が```u64```に収まらない場合はパニックする。
+impl<T: ToInteger + Order> ToInteger for Complex<T> {
+    fn to_integer(&self) -> u64 {
+        self.imaginary
+            .to_integer()
+            .checked_mul(T::order())
+            .and_then(|n| n.checked_add(self.real.to_integer()))
+            .expect("F_p^2 の元が u64 に収まりません。")
+    }
+}
+
+impl<T: FromInteger + Order> FromInteger for Complex<T> {
+    fn from_integer(n: u64) -> Option<Self> {
+        let q = T::order();
+        if n / q >= q {
+            return None;
+        }
+        Some(Complex::new(
+            T::from_integer(n % q)?,
+            T::from_integer(n / q)?,
+        ))
+    }
+}
+
+/// x = a + bi の平方根。-1 が```T```で平方元でない（p ≡ 3 mod 4）ことを想定している。
+///
+/// ノルム N = a^2 + b^2 の平方根 α をとると、(a ± α)/2 のどちらかが```T```の平方元 c^2 になり、
+/// c + (b / 2c)i が x の平方根になる。
+impl<T: Field + SquareRoot> SquareRoot for Complex<T> {
+    fn square_root(self) -> Option<Self> {
+        let (a, b) = (self.real, self.imaginary);
+        if b == T::zero() {
+            return match a.square_root() {
+                Some(c) => Some(Complex::new(c, T::zero())),
+                None => Some(Complex::new(T::zero(), (-a).square_root()?)),
+            };
+        }
+        let alpha = (a * a + b * b).square_root()?;
+        let half = (T::identity() + T::identity()).inverse()?;
+        let c = ((a + alpha) * half)
+            .square_root()
+            .or_else(|| ((a - alpha) * half).square_root())?;
+        let d = b.checked_div(&(c + c))?;
+        let root = Complex::new(c, d);
+        if root * root == self {
+            Some(root)
         } else {
-            Some(self.modpow(T::characteristic() * T::characteristic() - 2))
+            None
+        }
+    }
+}
+
+/// 実部の符号。実部が0の場合は虚部の符号。
+impl<T: Sign + Zero + Eq> Sign for Complex<T> {
+    fn sign(&self) -> bool {
+        self.real.sign() || (self.real == T::zero() && self.imaginary.sign())
+    }
+}
+
+/// 実部のバイト列の後に虚部のバイト列を続ける。
+impl<T: ByteEncoding> ByteEncoding for Complex<T> {
+    fn byte_length() -> usize {
+        2 * T::byte_length()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.real.to_bytes();
+        bytes.extend(self.imaginary.to_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let n = T::byte_length();
+        if bytes.len() != 2 * n {
+            return None;
+        }
+        Some(Complex::new(
+            T::from_bytes(&bytes[..n])?,
+            T::from_bytes(&bytes[n..])?,
+        ))
+    }
+}
+
+/// 実部と虚部を独立に一様にとる。
+impl<T: Random> Random for Complex<T> {
+    fn random(rng: &mut Rng) -> Self {
+        let real = T::random(rng);
+        Complex::new(real, T::random(rng))
+    }
+}
+
+/// p ≡ 3 mod 4 のとき i^p = -i なので、(a + bi)^p = a^p - b^p i となる。
+impl<T: FiniteField> FiniteField for Complex<T> {
+    fn degree() -> u32 {
+        2 * T::degree()
+    }
+
+    fn frobenius(self) -> Self {
+        Complex::new(self.real.frobenius(), -self.imaginary.frobenius())
+    }
+}
+
+/// ```Complex```の文字列表現の解析に失敗した理由。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseComplexError<E> {
+    /// 実部か虚部の解析に失敗した
+    Part(E),
+    /// ```a```、```bi```、```(a + bi)```のどの形でもない
+    Format,
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<E: fmt::Display> fmt::Display for ParseComplexError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseComplexError::Part(e) => write!(f, "実部か虚部が正しくありません：{}", e),
+            ParseComplexError::Format => write!(f, "a + bi の形になっていません。"),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ParseComplexError<E> {}
+
+// 以下は```num```クレートのトレイト。```num```の汎用的な関数をそのまま使えるようにする。
+
+impl<T: Ring> num::Zero for Complex<T> {
+    fn zero() -> Self {
+        <Self as Zero>::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == <Self as Zero>::zero()
+    }
+}
+
+impl<T: Ring> num::One for Complex<T> {
+    fn one() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Ring> Pow<u64> for Complex<T> {
+    type Output = Self;
+    fn pow(self, rhs: u64) -> Self {
+        self.modpow(rhs)
+    }
+}
+
+/// 逆元が存在しない元ではpanicする。panicさせたくない場合は```Inverse```を使う。
+impl<T: Field> Inv for Complex<T> {
+    type Output = Self;
+    fn inv(self) -> Self {
+        self.inverse().expect("逆元が存在しません。")
+    }
+}
+
+/// ```from_str_radix```は```Display```と同じ```a```、```bi```、```(a + bi)```の形を読む。
+/// 括弧と空白は省略でき、```a - bi```の形や、係数が1の```i```も読める。実部と虚部は```T```の```from_str_radix```で読む。
+/// 基数が19以上だと```i```が数字と区別できないので、```ParseComplexError::Format```を返す。
+impl<T: Field + Num> Num for Complex<T> {
+    type FromStrRadixErr = ParseComplexError<T::FromStrRadixErr>;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if radix > 18 {
+            return Err(ParseComplexError::Format);
+        }
+        let s: String = str.chars().filter(|c| !c.is_whitespace()).collect();
+        let s = match s.strip_prefix('(') {
+            Some(inner) => inner.strip_suffix(')').ok_or(ParseComplexError::Format)?,
+            None => &s,
+        };
+        let parse = |part: &str| T::from_str_radix(part, radix).map_err(ParseComplexError::Part);
+        let coefficient = |part: &str| match part {
+            "" | "+" => Ok(T::identity()),
+            "-" => Ok(-T::identity()),
+            _ => parse(part),
+        };
+        let rest = match s.strip_suffix('i') {
+            Some(rest) => rest,
+            None => return Ok(Complex::new(parse(s)?, <T as Zero>::zero())),
+        };
+        // 先頭以外で最後に現れる符号が、実部と虚部の区切り
+        match rest
+            .char_indices()
+            .skip(1)
+            .filter(|&(_, c)| c == '+' || c == '-')
+            .last()
+        {
+            Some((k, _)) => Ok(Complex::new(parse(&rest[..k])?, coefficient(&rest[k..])?)),
+            None => Ok(Complex::new(<T as Zero>::zero(), coefficient(rest)?)),
         }
     }
 }
@@ -0,0 +1,353 @@
+use crate::arithmetic::from_u64;
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::inverse::batch_inverse;
+use crate::rational_point::RationalPoint;
+use std::ops::Mul;
+
+/// 逆元を使わずに和と2倍を計算できる点の表し方。
+/// 途中の計算はこの表し方で行い、最後に```to_affine```で1回だけ逆元を計算してアフィン座標に戻す。
+/// aは y^2 = x^3 + ax + b の a 。
+pub trait Coordinates<T>
+where
+    Self: std::marker::Sized + Copy,
+{
+    /// アフィン座標の点から変換する。
+    fn from_affine(point: &RationalPoint<T>) -> Self;
+    /// アフィン座標に戻す。逆元を1回計算する。
+    fn to_affine(&self) -> RationalPoint<T>;
+    /// Z座標
+    fn z(&self) -> T;
+    /// Z の逆元が分かっているときにアフィン座標に戻す。O でない点に対してのみ使う。
+    fn to_affine_with_inverse(&self, z_inverse: T) -> RationalPoint<T>;
+    /// 無限遠点 O かどうか
+    fn is_infinity(&self) -> bool;
+    /// P + Q
+    fn add(&self, rhs: &Self, a: T) -> Self;
+    /// 2P
+    fn double(&self, a: T) -> Self;
+}
+
+/// 射影座標 (X : Y : Z) 。アフィン座標の (X/Z, Y/Z) を表し、O は (0 : 1 : 0) 。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ProjectivePoint<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+/// Jacobian座標 (X : Y : Z) 。アフィン座標の (X/Z^2, Y/Z^3) を表し、O は (1 : 1 : 0) 。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct JacobianPoint<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+/// Chudnovsky座標 (X : Y : Z : Z^2 : Z^3) 。Jacobian座標に Z^2 と Z^3 を持たせて、和の計算を減らしたもの。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ChudnovskyPoint<T> {
+    x: T,
+    y: T,
+    z: T,
+    z2: T,
+    z3: T,
+}
+
+impl<T: Copy> ProjectivePoint<T> {
+    /// (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl<T: Copy> JacobianPoint<T> {
+    /// (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl<T: Copy> ChudnovskyPoint<T> {
+    /// (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+}
+
+impl<T: Field> Coordinates<T> for ProjectivePoint<T> {
+    fn from_affine(point: &RationalPoint<T>) -> Self {
+        match *point {
+            RationalPoint::O => Self {
+                x: T::zero(),
+                y: T::identity(),
+                z: T::zero(),
+            },
+            RationalPoint::Point(x, y) => Self {
+                x,
+                y,
+                z: T::identity(),
+            },
+        }
+    }
+
+    fn to_affine(&self) -> RationalPoint<T> {
+        match self.z.inverse() {
+            None => RationalPoint::O,
+            Some(zi) => self.to_affine_with_inverse(zi),
+        }
+    }
+
+    fn z(&self) -> T {
+        self.z
+    }
+
+    fn to_affine_with_inverse(&self, z_inverse: T) -> RationalPoint<T> {
+        RationalPoint::Point(self.x * z_inverse, self.y * z_inverse)
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.z == T::zero()
+    }
+
+    fn add(&self, rhs: &Self, a: T) -> Self {
+        if self.is_infinity() {
+            return *rhs;
+        }
+        if rhs.is_infinity() {
+            return *self;
+        }
+        let (x1, y1, z1) = (self.x, self.y, self.z);
+        let (x2, y2, z2) = (rhs.x, rhs.y, rhs.z);
+        let u = y2 * z1 - y1 * z2;
+        let v = x2 * z1 - x1 * z2;
+        if v == T::zero() {
+            return if u == T::zero() {
+                self.double(a)
+            } else {
+                Self::from_affine(&RationalPoint::O)
+            };
+        }
+        let v2 = v * v;
+        let v3 = v2 * v;
+        let z1z2 = z1 * z2;
+        let x1z2 = x1 * z2;
+        let w = u * u * z1z2 - v3 - from_u64::<T>(2) * v2 * x1z2;
+        Self {
+            x: v * w,
+            y: u * (v2 * x1z2 - w) - v3 * y1 * z2,
+            z: v3 * z1z2,
+        }
+    }
+
+    fn double(&self, a: T) -> Self {
+        if self.is_infinity() || self.y == T::zero() {
+            return Self::from_affine(&RationalPoint::O);
+        }
+        let (x, y, z) = (self.x, self.y, self.z);
+        let w = a * z * z + from_u64::<T>(3) * x * x;
+        let s = y * z;
+        let b = x * y * s;
+        let h = w * w - from_u64::<T>(8) * b;
+        Self {
+            x: from_u64::<T>(2) * h * s,
+            y: w * (from_u64::<T>(4) * b - h) - from_u64::<T>(8) * y * y * s * s,
+            z: from_u64::<T>(8) * s * s * s,
+        }
+    }
+}
+
+impl<T: Field> Coordinates<T> for JacobianPoint<T> {
+    fn from_affine(point: &RationalPoint<T>) -> Self {
+        match *point {
+            RationalPoint::O => Self {
+                x: T::identity(),
+                y: T::identity(),
+                z: T::zero(),
+            },
+            RationalPoint::Point(x, y) => Self {
+                x,
+                y,
+                z: T::identity(),
+            },
+        }
+    }
+
+    fn to_affine(&self) -> RationalPoint<T> {
+        match self.z.inverse() {
+            None => RationalPoint::O,
+            Some(zi) => self.to_affine_with_inverse(zi),
+        }
+    }
+
+    fn z(&self) -> T {
+        self.z
+    }
+
+    fn to_affine_with_inverse(&self, z_inverse: T) -> RationalPoint<T> {
+        let zi2 = z_inverse * z_inverse;
+        RationalPoint::Point(self.x * zi2, self.y * zi2 * z_inverse)
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.z == T::zero()
+    }
+
+    fn add(&self, rhs: &Self, a: T) -> Self {
+        if self.is_infinity() {
+            return *rhs;
+        }
+        if rhs.is_infinity() {
+            return *self;
+        }
+        let z1z1 = self.z * self.z;
+        let z2z2 = rhs.z * rhs.z;
+        let u1 = self.x * z2z2;
+        let u2 = rhs.x * z1z1;
+        let s1 = self.y * z2z2 * rhs.z;
+        let s2 = rhs.y * z1z1 * self.z;
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double(a)
+            } else {
+                Self::from_affine(&RationalPoint::O)
+            };
+        }
+        let h = u2 - u1;
+        let r = s2 - s1;
+        let h2 = h * h;
+        let h3 = h2 * h;
+        let x = r * r - h3 - from_u64::<T>(2) * u1 * h2;
+        Self {
+            x,
+            y: r * (u1 * h2 - x) - s1 * h3,
+            z: h * self.z * rhs.z,
+        }
+    }
+
+    fn double(&self, a: T) -> Self {
+        if self.is_infinity() || self.y == T::zero() {
+            return Self::from_affine(&RationalPoint::O);
+        }
+        let (x, y, z) = (self.x, self.y, self.z);
+        let y2 = y * y;
+        let z2 = z * z;
+        let s = from_u64::<T>(4) * x * y2;
+        let m = from_u64::<T>(3) * x * x + a * z2 * z2;
+        let x3 = m * m - from_u64::<T>(2) * s;
+        Self {
+            x: x3,
+            y: m * (s - x3) - from_u64::<T>(8) * y2 * y2,
+            z: from_u64::<T>(2) * y * z,
+        }
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> ChudnovskyPoint<T> {
+    /// Jacobian座標から Z^2 と Z^3 を計算して作る。
+    fn from_jacobian(point: &JacobianPoint<T>) -> Self {
+        let z2 = point.z * point.z;
+        Self {
+            x: point.x,
+            y: point.y,
+            z: point.z,
+            z2,
+            z3: z2 * point.z,
+        }
+    }
+
+    fn to_jacobian(self) -> JacobianPoint<T> {
+        JacobianPoint {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+}
+
+impl<T: Field> Coordinates<T> for ChudnovskyPoint<T> {
+    fn from_affine(point: &RationalPoint<T>) -> Self {
+        Self::from_jacobian(&JacobianPoint::from_affine(point))
+    }
+
+    fn to_affine(&self) -> RationalPoint<T> {
+        self.to_jacobian().to_affine()
+    }
+
+    fn z(&self) -> T {
+        self.z
+    }
+
+    fn to_affine_with_inverse(&self, z_inverse: T) -> RationalPoint<T> {
+        self.to_jacobian().to_affine_with_inverse(z_inverse)
+    }
+
+    fn is_infinity(&self) -> bool {
+        self.z == T::zero()
+    }
+
+    /// Jacobian座標の和と同じだが、持っている Z^2, Z^3 を使って乗算を減らす。
+    fn add(&self, rhs: &Self, a: T) -> Self {
+        if self.is_infinity() {
+            return *rhs;
+        }
+        if rhs.is_infinity() {
+            return *self;
+        }
+        let u1 = self.x * rhs.z2;
+        let u2 = rhs.x * self.z2;
+        let s1 = self.y * rhs.z3;
+        let s2 = rhs.y * self.z3;
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double(a)
+            } else {
+                Self::from_affine(&RationalPoint::O)
+            };
+        }
+        let h = u2 - u1;
+        let r = s2 - s1;
+        let h2 = h * h;
+        let h3 = h2 * h;
+        let x = r * r - h3 - from_u64::<T>(2) * u1 * h2;
+        Self::from_jacobian(&JacobianPoint {
+            x,
+            y: r * (u1 * h2 - x) - s1 * h3,
+            z: h * self.z * rhs.z,
+        })
+    }
+
+    fn double(&self, a: T) -> Self {
+        Self::from_jacobian(&self.to_jacobian().double(a))
+    }
+}
+
+/// 点の列をまとめてアフィン座標に戻す。Z座標の逆元は```batch_inverse```で1回の逆元計算にまとめる。
+pub fn batch_to_affine<T: Field, C: Coordinates<T>>(points: &[C]) -> Vec<RationalPoint<T>> {
+    let zs: Vec<T> = points.iter().map(|p| p.z()).collect();
+    points
+        .iter()
+        .zip(batch_inverse(&zs))
+        .map(|(p, zi)| match zi {
+            None => RationalPoint::O,
+            Some(zi) => p.to_affine_with_inverse(zi),
+        })
+        .collect()
+}
+
+impl<T: Field> EllipticCurve<T> {
+    /// スカラー倍 [n]P を座標系```C```で計算する。逆元は最後にアフィン座標へ戻すときの1回だけ。
+    pub fn multiply_in<C: Coordinates<T>>(&self, p: &RationalPoint<T>, n: u64) -> RationalPoint<T> {
+        let mut res = C::from_affine(&RationalPoint::O);
+        let mut q = C::from_affine(p);
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res = res.add(&q, self.a());
+            }
+            q = q.double(self.a());
+            m /= 2;
+        }
+        res.to_affine()
+    }
+}
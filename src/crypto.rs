@@ -0,0 +1,356 @@
+use crate::elliptic_curve::EllipticCurve;
+use crate::encoding::ByteEncoding;
+use crate::field::Field;
+use crate::finite_field::FiniteField;
+use crate::integer::ToInteger;
+use crate::number_theory::{is_prime, mod_inverse, mul_mod};
+use crate::rational_point::RationalPoint;
+use crate::sign::Sign;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// 鍵や署名を扱えないときのエラー
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CryptoError {
+    /// 曲線・基点・位数の組が正しくない
+    InvalidDomain,
+    /// 秘密鍵が 1 以上 n 未満ではない
+    InvalidPrivateKey,
+    /// 公開鍵が曲線上にないか、基点の生成する部分群に入っていない
+    InvalidPublicKey,
+    /// バイト列の長さや形式が正しくない
+    InvalidEncoding,
+    /// 暗号化に使う乱数が 1 以上 n 未満ではない
+    InvalidNonce,
+    /// 平文の点が曲線上にない
+    InvalidMessage,
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::InvalidDomain => write!(f, "曲線・基点・位数の組が正しくありません。"),
+            CryptoError::InvalidPrivateKey => write!(f, "秘密鍵が範囲外です。"),
+            CryptoError::InvalidPublicKey => write!(f, "公開鍵が正しい点ではありません。"),
+            CryptoError::InvalidEncoding => write!(f, "バイト列の形式が正しくありません。"),
+            CryptoError::InvalidNonce => write!(f, "乱数が範囲外です。"),
+            CryptoError::InvalidMessage => write!(f, "平文が曲線上の点ではありません。"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// 秘密鍵。1 以上 n 未満の整数。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PrivateKey(u64);
+
+impl PrivateKey {
+    /// 整数としての値
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// 8バイトのビッグエンディアン
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+/// 公開鍵。秘密鍵dに対する点 [d]G 。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct PublicKey<T>(RationalPoint<T>);
+
+impl<T: Copy> PublicKey<T> {
+    /// 点としての値
+    pub fn point(&self) -> RationalPoint<T> {
+        self.0
+    }
+}
+
+impl<T: ByteEncoding + Sign> PublicKey<T> {
+    /// 圧縮しない点の表現（```RationalPoint::to_uncompressed_bytes```）。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_uncompressed_bytes()
+    }
+
+    /// 圧縮した点の表現（```RationalPoint::to_compressed_bytes```）。
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed_bytes()
+    }
+}
+
+/// ECDSA署名 (r, s) 。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Signature {
+    r: u64,
+    s: u64,
+}
+
+impl Signature {
+    /// r
+    pub fn r(&self) -> u64 {
+        self.r
+    }
+
+    /// s
+    pub fn s(&self) -> u64 {
+        self.s
+    }
+
+    /// r と s を8バイトずつビッグエンディアンで並べる。
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.r.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.s.to_be_bytes());
+        bytes
+    }
+
+    /// ```to_bytes```の逆。長さが16バイトでない場合は```None```を返す。
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 16 {
+            return None;
+        }
+        Some(Self {
+            r: read_u64(&bytes[0..8]),
+            s: read_u64(&bytes[8..16]),
+        })
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(r, s) = ({}, {})", self.r, self.s)
+    }
+}
+
+/// 8バイトのビッグエンディアンを整数にする。
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_be_bytes(buf)
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC-SHA256 をいくつかのバイト列をつなげたものに対して計算する。
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).unwrap();
+    for d in data {
+        mac.update(d);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 曲線・位数nの基点G の組（ドメインパラメータ）。nは素数。
+///
+/// 教育用の実装で、位数が```u64```に収まる小さな群しか扱えず、安全性はない。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Domain<T> {
+    curve: EllipticCurve<T>,
+    generator: RationalPoint<T>,
+    order: u64,
+}
+
+impl<T: Copy> Domain<T> {
+    /// 曲線
+    pub fn curve(&self) -> EllipticCurve<T> {
+        self.curve
+    }
+
+    /// 基点G
+    pub fn generator(&self) -> RationalPoint<T> {
+        self.generator
+    }
+
+    /// 基点の位数n
+    pub fn order(&self) -> u64 {
+        self.order
+    }
+}
+
+impl<T: Field + ToInteger> Domain<T> {
+    /// コンストラクタ。Gが曲線上の O でない点で、位数がちょうど素数nであることを確かめる。
+    pub fn new(
+        curve: EllipticCurve<T>,
+        generator: RationalPoint<T>,
+        order: u64,
+    ) -> Result<Self, CryptoError> {
+        if curve.is_singular()
+            || generator == RationalPoint::O
+            || !curve.contains(&generator)
+            || !is_prime(order)
+            || curve.multiply(&generator, order) != RationalPoint::O
+        {
+            return Err(CryptoError::InvalidDomain);
+        }
+        Ok(Self {
+            curve,
+            generator,
+            order,
+        })
+    }
+
+    /// nのビット長
+    fn bit_length(&self) -> u32 {
+        64 - self.order.leading_zeros()
+    }
+
+    /// RFC 6979 の bits2int 。バイト列の先頭からnのビット長分を取り出して整数にする。
+    fn bits_to_int(&self, bytes: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        let len = bytes.len().min(8);
+        buf[..len].copy_from_slice(&bytes[..len]);
+        let value = u64::from_be_bytes(buf);
+        let bits = (8 * bytes.len()).min(64) as u32;
+        if bits > self.bit_length() {
+            value >> (64 - self.bit_length())
+        } else {
+            value >> (64 - bits)
+        }
+    }
+
+    /// RFC 6979 の int2octets 。nのバイト長のビッグエンディアンにする。
+    fn int_to_octets(&self, x: u64) -> Vec<u8> {
+        let len = self.bit_length().div_ceil(8) as usize;
+        x.to_be_bytes()[(8 - len)..].to_vec()
+    }
+
+    /// 秘密鍵を作る。1 以上 n 未満でなければエラーを返す。
+    pub fn private_key(&self, d: u64) -> Result<PrivateKey, CryptoError> {
+        if d == 0 || d >= self.order {
+            return Err(CryptoError::InvalidPrivateKey);
+        }
+        Ok(PrivateKey(d))
+    }
+
+    /// ```PrivateKey::to_bytes```の逆。
+    pub fn private_key_from_bytes(&self, bytes: &[u8]) -> Result<PrivateKey, CryptoError> {
+        if bytes.len() != 8 {
+            return Err(CryptoError::InvalidEncoding);
+        }
+        self.private_key(read_u64(bytes))
+    }
+
+    /// 秘密鍵dに対する公開鍵 [d]G 。
+    pub fn public_key(&self, private_key: &PrivateKey) -> PublicKey<T> {
+        PublicKey(self.curve.multiply(&self.generator, private_key.0))
+    }
+
+    /// 点が公開鍵として正しいか（曲線上の O でない点で、[n]Q = O となるか）確かめて公開鍵にする。
+    pub fn validate_public_key(
+        &self,
+        point: &RationalPoint<T>,
+    ) -> Result<PublicKey<T>, CryptoError> {
+        if *point == RationalPoint::O
+            || !self.curve.contains(point)
+            || self.curve.multiply(point, self.order) != RationalPoint::O
+        {
+            return Err(CryptoError::InvalidPublicKey);
+        }
+        Ok(PublicKey(*point))
+    }
+
+    /// ECDH。自分の秘密鍵dと相手の公開鍵Qから共有点 [d]Q を求め、そのx座標を整数にしたものを返す。
+    pub fn shared_secret(
+        &self,
+        private_key: &PrivateKey,
+        public_key: &PublicKey<T>,
+    ) -> Result<u64, CryptoError> {
+        let public_key = self.validate_public_key(&public_key.0)?;
+        match self.curve.multiply(&public_key.0, private_key.0) {
+            RationalPoint::O => Err(CryptoError::InvalidPublicKey),
+            RationalPoint::Point(x, _) => Ok(x.to_integer()),
+        }
+    }
+
+    /// 点のx座標を整数にして n で割った余り。F_p^2 の元は```ToInteger```で整数にする。
+    fn x_mod_order(&self, point: &RationalPoint<T>) -> Option<u64> {
+        match point {
+            RationalPoint::O => None,
+            RationalPoint::Point(x, _) => Some(x.to_integer() % self.order),
+        }
+    }
+
+    /// RFC 6979 (3.2節) にならって、秘密鍵とメッセージのハッシュ値 h1 から決定的にnonceの候補を作る。
+    /// 候補は1 以上 n 未満で、署名に使えなかった場合は次の候補を使う。
+    fn nonces(&self, private_key: &PrivateKey, h1: &[u8]) -> impl Iterator<Item = u64> + '_ {
+        let x = self.int_to_octets(private_key.0);
+        let h = self.int_to_octets(self.bits_to_int(h1) % self.order);
+        let mut v = vec![0x01u8; 32];
+        let mut k = vec![0x00u8; 32];
+        k = hmac_sha256(&k, &[&v, &[0x00], &x, &h]);
+        v = hmac_sha256(&k, &[&v]);
+        k = hmac_sha256(&k, &[&v, &[0x01], &x, &h]);
+        v = hmac_sha256(&k, &[&v]);
+        let mut first = true;
+        std::iter::from_fn(move || loop {
+            if !first {
+                k = hmac_sha256(&k, &[&v, &[0x00]]);
+                v = hmac_sha256(&k, &[&v]);
+            }
+            first = false;
+            // SHA-256 の出力は 256 ビットあり、nのビット長（64以下）より長いので1回で足りる
+            v = hmac_sha256(&k, &[&v]);
+            let candidate = self.bits_to_int(&v);
+            if 1 <= candidate && candidate < self.order {
+                return Some(candidate);
+            }
+        })
+    }
+
+    /// ECDSA署名。メッセージはSHA-256でハッシュし、nonceはRFC 6979にならって決定的に作る。
+    pub fn sign(&self, private_key: &PrivateKey, message: &[u8]) -> Signature {
+        let h1 = Sha256::digest(message);
+        let e = self.bits_to_int(&h1) % self.order;
+        for k in self.nonces(private_key, &h1) {
+            let r = match self.x_mod_order(&self.curve.multiply(&self.generator, k)) {
+                Some(r) if r != 0 => r,
+                _ => continue,
+            };
+            let k_inv = mod_inverse(k, self.order).unwrap();
+            let s = mul_mod(
+                k_inv,
+                (e + mul_mod(r, private_key.0, self.order)) % self.order,
+                self.order,
+            );
+            if s != 0 {
+                return Signature { r, s };
+            }
+        }
+        unreachable!("nonceの候補は尽きません。")
+    }
+
+    /// ECDSA署名の検証。
+    pub fn verify(&self, public_key: &PublicKey<T>, message: &[u8], signature: &Signature) -> bool {
+        let n = self.order;
+        let Signature { r, s } = *signature;
+        if r == 0 || r >= n || s == 0 || s >= n || self.validate_public_key(&public_key.0).is_err()
+        {
+            return false;
+        }
+        let e = self.bits_to_int(&Sha256::digest(message)) % n;
+        let w = mod_inverse(s, n).unwrap();
+        let u1 = mul_mod(e, w, n);
+        let u2 = mul_mod(r, w, n);
+        let point = self.curve.add(
+            &self.curve.multiply(&self.generator, u1),
+            &self.curve.multiply(&public_key.0, u2),
+        );
+        self.x_mod_order(&point) == Some(r)
+    }
+}
+
+impl<T: FiniteField + ToInteger + Sign + ByteEncoding> Domain<T> {
+    /// ```PublicKey::to_bytes```と```PublicKey::to_compressed_bytes```の逆。
+    /// 点が公開鍵として正しいかも確かめる。
+    pub fn public_key_from_bytes(&self, bytes: &[u8]) -> Result<PublicKey<T>, CryptoError> {
+        let point = self
+            .curve
+            .point_from_bytes(bytes)
+            .ok_or(CryptoError::InvalidEncoding)?;
+        self.validate_public_key(&point)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::arithmetic::power;
+use crate::elements::{Elements, Order};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::number_theory::{crt, factorize, mod_inverse, mul_mod};
+use crate::rational_point::RationalPoint;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// 有限体の乗法群での Baby-step giant-step 法。β = α^k となる k (0 ≤ k < n) を求める。nはαの位数。
+/// βがαの生成する部分群に入っていない場合は```None```を返す。
+pub fn field_baby_step_giant_step<T: Field + Hash>(alpha: T, beta: T, n: u64) -> Option<u64> {
+    let m = (n as f64).sqrt().ceil() as u64;
+    let mut baby_steps: HashMap<T, u64> = HashMap::new();
+    let mut r = T::identity();
+    for j in 0..m {
+        baby_steps.entry(r).or_insert(j);
+        r *= alpha;
+    }
+    let giant = power(alpha, m).inverse()?;
+    let mut r = beta;
+    for i in 0..=m {
+        if let Some(&j) = baby_steps.get(&r) {
+            return Some((i * m + j) % n);
+        }
+        r *= giant;
+    }
+    None
+}
+
+/// Pollardのρ法で使う r-adding walk の分割数
+const PARTITIONS: usize = 16;
+
+/// 点のハッシュ値。ρ法のランダムウォークの行き先と distinguished point の判定に使う。
+fn point_hash<T: Hash>(point: &RationalPoint<T>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    point.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// ρ法の初期値や歩幅を決めるための簡単な擬似乱数（splitmix64）
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+impl<T: Field + Hash> EllipticCurve<T> {
+    /// Baby-step giant-step 法で Q = [k]P となる k (0 ≤ k < n) を求める。nはPの位数。
+    /// QがPの生成する部分群に入っていない場合は```None```を返す。
+    pub fn baby_step_giant_step(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Option<u64> {
+        let m = (n as f64).sqrt().ceil() as u64;
+        let mut baby_steps: HashMap<RationalPoint<T>, u64> = HashMap::new();
+        let mut r = RationalPoint::O;
+        for j in 0..m {
+            baby_steps.entry(r).or_insert(j);
+            r = self.add(&r, p);
+        }
+        let giant = -self.multiply(p, m);
+        let mut r = *q;
+        for i in 0..=m {
+            if let Some(&j) = baby_steps.get(&r) {
+                let k = (i * m + j) % n;
+                return Some(k);
+            }
+            r = self.add(&r, &giant);
+        }
+        None
+    }
+
+    /// Pollardのρ法（distinguished point を使うもの）で Q = [k]P となる k (0 ≤ k < n) を求める。
+    /// nはPの位数で、素数であることを想定している。
+    ///
+    /// 点を aP + bQ の形で持ち、r-adding walk で進めて distinguished point だけを記録する。
+    /// 同じ distinguished point に異なる (a, b) でたどり着いたら a1 + b1 k ≡ a2 + b2 k (mod n) を解く。
+    pub fn pollard_rho(&self, p: &RationalPoint<T>, q: &RationalPoint<T>, n: u64) -> Option<u64> {
+        if n <= 16 {
+            return self.baby_step_giant_step(p, q, n);
+        }
+        if *q == RationalPoint::O {
+            return Some(0);
+        }
+        let mut state = 0x5eed;
+        let combination = |a: u64, b: u64| self.add(&self.multiply(p, a), &self.multiply(q, b));
+        let steps: Vec<(u64, u64, RationalPoint<T>)> = (0..PARTITIONS)
+            .map(|_| {
+                let a = splitmix64(&mut state) % n;
+                let b = splitmix64(&mut state) % n;
+                (a, b, combination(a, b))
+            })
+            .collect();
+        // 期待される歩数 sqrt(n) の 1/8 程度ごとに distinguished point が現れるようにする
+        let bits = ((n as f64).sqrt().log2() as u32).saturating_sub(3);
+        let mask = (1u64 << bits) - 1;
+        let max_walk = 20 * (mask + 1);
+        let mut distinguished: HashMap<RationalPoint<T>, (u64, u64)> = HashMap::new();
+        for _ in 0..(4 * n) {
+            let mut a = splitmix64(&mut state) % n;
+            let mut b = splitmix64(&mut state) % n;
+            let mut x = combination(a, b);
+            for _ in 0..max_walk {
+                let h = point_hash(&x);
+                if h & mask == 0 {
+                    match distinguished.get(&x) {
+                        None => {
+                            distinguished.insert(x, (a, b));
+                        }
+                        Some(&(a2, b2)) => {
+                            if let Some(k) = self.solve_collision(p, q, n, (a, b), (a2, b2)) {
+                                return Some(k);
+                            }
+                        }
+                    }
+                    break;
+                }
+                let (da, db, step) = &steps[(h >> 32) as usize % PARTITIONS];
+                x = self.add(&x, step);
+                a = (a + da) % n;
+                b = (b + db) % n;
+            }
+        }
+        None
+    }
+
+    /// a1 P + b1 Q = a2 P + b2 Q から (b1 - b2)k ≡ a2 - a1 (mod n) を解き、実際に Q = [k]P となる k を返す。
+    fn solve_collision(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+        (a1, b1): (u64, u64),
+        (a2, b2): (u64, u64),
+    ) -> Option<u64> {
+        let db = (b1 + n - b2) % n;
+        let da = (a2 + n - a1) % n;
+        let g = num::Integer::gcd(&db, &n);
+        if db == 0 || !da.is_multiple_of(g) {
+            return None;
+        }
+        let m = n / g;
+        let k0 = mul_mod(da / g, mod_inverse(db / g, m).unwrap(), m);
+        (0..g)
+            .map(|t| k0 + t * m)
+            .find(|&k| self.multiply(p, k) == *q)
+    }
+
+    /// Pohlig–Hellman法で Q = [k]P となる k (0 ≤ k < n) を求める。nはPの位数。
+    /// nを素因数分解し、各素数冪 l^e について k mod l^e を位数lの部分群での離散対数から1桁ずつ求め、
+    /// 中国剰余定理でまとめる。位数lの部分群での離散対数はρ法で解く。
+    pub fn pohlig_hellman(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Option<u64> {
+        let mut congruences: Vec<(u64, u64)> = Vec::new();
+        for (l, e) in factorize(n) {
+            let le = l.pow(e);
+            let p_l = self.multiply(p, n / le);
+            let q_l = self.multiply(q, n / le);
+            let gamma = self.multiply(&p_l, le / l);
+            let mut x = 0;
+            let mut l_i = 1;
+            for i in 0..e {
+                let h = self.multiply(&self.add(&q_l, &-self.multiply(&p_l, x)), l.pow(e - 1 - i));
+                let d = self.pollard_rho(&gamma, &h, l)?;
+                x += d * l_i;
+                l_i *= l;
+            }
+            congruences.push((x, le));
+        }
+        let (k, _) = crt(&congruences);
+        if self.multiply(p, k) == *q {
+            Some(k)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Field + Hash + Order + Elements> EllipticCurve<T> {
+    /// Q = [k]P となる k を求める。Pの位数を計算してからPohlig–Hellman法で解く。
+    pub fn discrete_log(&self, p: &RationalPoint<T>, q: &RationalPoint<T>) -> Option<u64> {
+        let n = self.order_of_point(p);
+        self.pohlig_hellman(p, q, n)
+    }
+}
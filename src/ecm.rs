@@ -0,0 +1,287 @@
+use crate::elliptic_curve::EllipticCurve;
+use crate::modint::ModInt;
+use crate::number_theory::{is_prime, mod_inverse, mul_mod};
+use crate::random::Rng;
+use crate::rational_point::RationalPoint;
+use num::Integer;
+
+/// 実行時に与える N を法とする曲線 y^2 = x^3 + ax + b の群演算。座標は 0 以上 N 未満の```u64```で持つ。
+/// ```ModInt```は法を型引数で与えるので、コマンドライン引数などで与えた N を扱うためにこちらを使う。
+///
+/// N が素数でないと逆元が計算できないことがあり、そのときは分母と N の最大公約数を```Err```で返す。
+/// この最大公約数が 1 でも N でもなければ、N の非自明な約数になる。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+struct CurveModN {
+    modulus: u64,
+    a: u64,
+}
+
+impl CurveModN {
+    /// コンストラクタ。bは足し算に使わないので持たない。
+    fn new(modulus: u64, a: u64) -> Self {
+        Self {
+            modulus,
+            a: a % modulus,
+        }
+    }
+
+    /// x + y mod N
+    fn add_mod(&self, x: u64, y: u64) -> u64 {
+        ((x as u128 + y as u128) % self.modulus as u128) as u64
+    }
+
+    /// x - y mod N
+    fn sub_mod(&self, x: u64, y: u64) -> u64 {
+        ((x as u128 + self.modulus as u128 - y as u128) % self.modulus as u128) as u64
+    }
+
+    /// x * y mod N
+    fn mul_mod(&self, x: u64, y: u64) -> u64 {
+        mul_mod(x, y, self.modulus)
+    }
+
+    /// 足し算。分母の逆元が計算できなかった場合は、分母と N の最大公約数を```Err```で返す。
+    fn try_add(
+        &self,
+        p: &RationalPoint<u64>,
+        q: &RationalPoint<u64>,
+    ) -> Result<RationalPoint<u64>, u64> {
+        let ((x1, y1), (x2, y2)) = match (*p, *q) {
+            (RationalPoint::O, _) => return Ok(*q),
+            (_, RationalPoint::O) => return Ok(*p),
+            (RationalPoint::Point(x1, y1), RationalPoint::Point(x2, y2)) => ((x1, y1), (x2, y2)),
+        };
+        let gcd = |d: u64| d.gcd(&self.modulus);
+        let (numerator, denominator) = if x1 != x2 {
+            (self.sub_mod(y2, y1), self.sub_mod(x2, x1))
+        } else if self.add_mod(y1, y2) == 0 {
+            return Ok(RationalPoint::O);
+        } else if y1 == y2 {
+            let x1_squared = self.mul_mod(x1, x1);
+            let numerator = self.add_mod(
+                self.add_mod(x1_squared, x1_squared),
+                self.add_mod(x1_squared, self.a),
+            );
+            (numerator, self.add_mod(y1, y1))
+        } else {
+            // x1 = x2 かつ y1 ≠ ±y2 は N が合成数のときだけ起こり、(y1 - y2)(y1 + y2) ≡ 0 となる
+            return Err(gcd(self.sub_mod(y1, y2)));
+        };
+        let inverse = mod_inverse(denominator, self.modulus).ok_or_else(|| gcd(denominator))?;
+        let m = self.mul_mod(numerator, inverse);
+        let x3 = self.sub_mod(self.sub_mod(self.mul_mod(m, m), x1), x2);
+        let y3 = self.sub_mod(self.mul_mod(m, self.sub_mod(x1, x3)), y1);
+        Ok(RationalPoint::Point(x3, y3))
+    }
+
+    /// スカラー倍 [n]P 。途中の足し算で逆元が計算できなかった場合は、その最大公約数を```Err```で返す。
+    fn try_multiply(&self, p: &RationalPoint<u64>, n: u64) -> Result<RationalPoint<u64>, u64> {
+        let mut res = RationalPoint::O;
+        let mut q = *p;
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res = self.try_add(&res, &q)?;
+            }
+            m /= 2;
+            if m > 0 {
+                q = self.try_add(&q, &q)?;
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// ```ModInt```の点を代表元の組にする。
+fn to_residues<const N: u64>(p: &RationalPoint<ModInt<N>>) -> RationalPoint<u64> {
+    match *p {
+        RationalPoint::O => RationalPoint::O,
+        RationalPoint::Point(x, y) => RationalPoint::Point(x.to_int(), y.to_int()),
+    }
+}
+
+/// 代表元の組を```ModInt```の点に戻す。
+fn from_residues<const N: u64>(p: RationalPoint<u64>) -> RationalPoint<ModInt<N>> {
+    match p {
+        RationalPoint::O => RationalPoint::O,
+        RationalPoint::Point(x, y) => RationalPoint::Point(ModInt::new(x), ModInt::new(y)),
+    }
+}
+
+/// 合成数 N を法とする Z / NZ 上の曲線の群演算。
+///
+/// N が素数でないと逆元が計算できないことがあり、そのときは分母と N の最大公約数を```Err```で返す。
+/// この最大公約数が 1 でも N でもなければ、N の非自明な約数になる。
+impl<const N: u64> EllipticCurve<ModInt<N>> {
+    /// 足し算。分母の逆元が計算できなかった場合は、分母と N の最大公約数を```Err```で返す。
+    pub fn try_add(
+        &self,
+        p: &RationalPoint<ModInt<N>>,
+        q: &RationalPoint<ModInt<N>>,
+    ) -> Result<RationalPoint<ModInt<N>>, u64> {
+        CurveModN::new(N, self.a().to_int())
+            .try_add(&to_residues(p), &to_residues(q))
+            .map(from_residues)
+    }
+
+    /// スカラー倍 [n]P 。途中の足し算で逆元が計算できなかった場合は、その最大公約数を```Err```で返す。
+    pub fn try_multiply(
+        &self,
+        p: &RationalPoint<ModInt<N>>,
+        n: u64,
+    ) -> Result<RationalPoint<ModInt<N>>, u64> {
+        CurveModN::new(N, self.a().to_int())
+            .try_multiply(&to_residues(p), n)
+            .map(from_residues)
+    }
+}
+
+/// エラトステネスの篩で n 以下の素数を全て求める。
+fn primes_up_to(n: u64) -> Vec<u64> {
+    let n = n as usize;
+    let mut sieve = vec![true; n + 1];
+    let mut primes = Vec::new();
+    for i in 2..=n {
+        if sieve[i] {
+            primes.push(i as u64);
+            for j in (i * i..=n).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+/// Lenstraの楕円曲線法 (ECM) による素因数探索。
+///
+/// Z / NZ 上のランダムな曲線と点 Q をとり、[k]Q を計算する。N の素因数 r について #E(F_r) が
+/// k を割り切れば、[k]Q は r を法として O になるので、途中の足し算で r の倍数の逆元が必要になり失敗する。
+/// そのときの分母と N の最大公約数が N の約数になる。
+///
+/// 第1段階では k を```stage1_bound```以下の素数冪の積とし、第2段階ではさらに
+/// ```stage1_bound```より大きく```stage2_bound```以下の素数 ℓ を1つずつ試して [ℓ]([k]Q) を調べる。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Ecm {
+    stage1_bound: u64,
+    stage2_bound: u64,
+    curves: u32,
+}
+
+impl Ecm {
+    /// コンストラクタ。第1段階と第2段階の上限、試す曲線の個数を与える。
+    /// ```stage2_bound```が```stage1_bound```以下の場合は第2段階を行わない。
+    pub fn new(stage1_bound: u64, stage2_bound: u64, curves: u32) -> Self {
+        Self {
+            stage1_bound,
+            stage2_bound: stage2_bound.max(stage1_bound),
+            curves,
+        }
+    }
+
+    /// 第1段階の上限 B1
+    pub fn stage1_bound(&self) -> u64 {
+        self.stage1_bound
+    }
+
+    /// 第2段階の上限 B2
+    pub fn stage2_bound(&self) -> u64 {
+        self.stage2_bound
+    }
+
+    /// 試す曲線の個数
+    pub fn curves(&self) -> u32 {
+        self.curves
+    }
+
+    /// n の非自明な約数（1 でも n でもない約数）を1つ探す。
+    /// 曲線 y^2 = x^3 + ax + b を使うので、2 と 3 は先に割って調べる。
+    /// n が素数か 1 以下の場合、または```curves```個の曲線で見つからなかった場合は```None```を返す。
+    pub fn find_factor(&self, n: u64, rng: &mut Rng) -> Option<u64> {
+        if n < 4 || is_prime(n) {
+            return None;
+        }
+        for d in [2, 3] {
+            if n.is_multiple_of(d) {
+                return Some(d);
+            }
+        }
+        let primes = primes_up_to(self.stage2_bound);
+        let (stage1, stage2) = primes.split_at(primes.partition_point(|&l| l <= self.stage1_bound));
+        for _ in 0..self.curves {
+            // 点 (x, y) を先に決め、それを通るように b を決める
+            let (x, y, a) = (rng.below(n), rng.below(n), rng.below(n));
+            let curve = CurveModN::new(n, a);
+            let x_cubed = curve.mul_mod(curve.mul_mod(x, x), x);
+            let b = curve.sub_mod(
+                curve.mul_mod(y, y),
+                curve.add_mod(x_cubed, curve.mul_mod(a, x)),
+            );
+            // 判別式 -16(4a^3 + 27b^2) の 16 は n と互いに素なので、4a^3 + 27b^2 と n の最大公約数を見る
+            let a_cubed = curve.mul_mod(curve.mul_mod(a, a), a);
+            let discriminant = curve
+                .add_mod(
+                    curve.mul_mod(4, a_cubed),
+                    curve.mul_mod(27, curve.mul_mod(b, b)),
+                )
+                .gcd(&n);
+            if discriminant == n {
+                continue;
+            }
+            if discriminant != 1 {
+                return Some(discriminant);
+            }
+            match self.run_curve(&curve, RationalPoint::Point(x, y), stage1, stage2) {
+                Err(d) if d != n => return Some(d),
+                _ => continue,
+            }
+        }
+        None
+    }
+
+    /// 1つの曲線で第1段階と第2段階を行う。約数が見つかれば```Err```で返す。
+    fn run_curve(
+        &self,
+        curve: &CurveModN,
+        point: RationalPoint<u64>,
+        stage1: &[u64],
+        stage2: &[u64],
+    ) -> Result<(), u64> {
+        // 第1段階：B1 以下の素数 ℓ について、ℓ^e ≤ B1 となる最大の ℓ^e 倍する
+        let mut q = point;
+        for &l in stage1 {
+            let mut power = l;
+            while power <= self.stage1_bound / l {
+                power *= l;
+            }
+            q = curve.try_multiply(&q, power)?;
+            if q == RationalPoint::O {
+                return Ok(());
+            }
+        }
+        // 第2段階：連続する素数の差 (偶数) ごとに [d]Q を用意しておき、[ℓ]Q に順に足していく
+        let (first, rest) = match stage2.split_first() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        let mut differences: Vec<RationalPoint<u64>> = vec![RationalPoint::O];
+        let mut r = curve.try_multiply(&q, *first)?;
+        let mut previous = *first;
+        for &l in rest {
+            let d = ((l - previous) / 2) as usize;
+            while differences.len() <= d {
+                let next = curve.try_add(&differences[differences.len() - 1], &q)?;
+                differences.push(curve.try_add(&next, &q)?);
+            }
+            r = curve.try_add(&r, &differences[d])?;
+            previous = l;
+        }
+        Ok(())
+    }
+}
+
+/// 試す曲線の個数を多めにとった、2^64 未満の合成数向けの設定 (B1 = 2000, B2 = 100000, 曲線 200 個)。
+impl Default for Ecm {
+    fn default() -> Self {
+        Self::new(2000, 100000, 200)
+    }
+}
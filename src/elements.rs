@@ -0,0 +1,12 @@
+/// 有限集合の元の個数（有限体の位数）を与える。
+pub trait Order {
+    fn order() -> u64;
+}
+
+/// 有限集合の元を全て並べたものを与える。
+pub trait Elements
+where
+    Self: std::marker::Sized,
+{
+    fn elements() -> Vec<Self>;
+}
@@ -0,0 +1,140 @@
+use crate::crypto::{CryptoError, Domain, PrivateKey, PublicKey};
+use crate::elements::Order;
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::integer::{FromInteger, ToInteger};
+use crate::rational_point::RationalPoint;
+use crate::ring::Ring;
+use crate::square_root::SquareRoot;
+use std::fmt;
+
+/// EC-ElGamal の暗号文 (C1, C2) = ([k]G, M + [k]Q) 。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Ciphertext<T> {
+    c1: RationalPoint<T>,
+    c2: RationalPoint<T>,
+}
+
+impl<T: Copy> Ciphertext<T> {
+    /// C1 = [k]G
+    pub fn c1(&self) -> RationalPoint<T> {
+        self.c1
+    }
+
+    /// C2 = M + [k]Q
+    pub fn c2(&self) -> RationalPoint<T> {
+        self.c2
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<T: fmt::Display> fmt::Display for Ciphertext<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(C1, C2) = ({}, {})", self.c1, self.c2)
+    }
+}
+
+impl<T: Ring + SquareRoot + ToInteger + FromInteger + Order> EllipticCurve<T> {
+    /// Koblitzの方法で整数mを点にする。
+    ///
+    /// x座標の候補を m * padding + j (j = 0, 1, ..., padding - 1) の順に```FromInteger```で体の元にし、
+    /// x^3 + ax + b の平方根がとれたところでその点を返す。
+    /// 1つの候補で失敗する確率はおよそ1/2なので、失敗する確率は 2^(-padding) 程度。
+    /// 候補が体の位数を超えるか、すべての候補で失敗した場合は```None```を返す。
+    pub fn encode_integer(&self, m: u64, padding: u64) -> Option<RationalPoint<T>> {
+        for j in 0..padding {
+            let x = T::from_integer(m.checked_mul(padding)?.checked_add(j)?)?;
+            if let Some(y) = (x * x * x + self.a() * x + self.b()).square_root() {
+                return Some(RationalPoint::Point(x, y));
+            }
+        }
+        None
+    }
+
+    /// ```encode_integer```の逆。x座標を整数にして padding で割る。
+    pub fn decode_integer(&self, point: &RationalPoint<T>, padding: u64) -> Option<u64> {
+        match point {
+            RationalPoint::O => None,
+            RationalPoint::Point(x, _) => Some(x.to_integer() / padding),
+        }
+    }
+
+    /// 1つの点に入るバイト数。256^c * padding ≤ q となる最大の c 。1バイトも入らない場合は```None```。
+    fn chunk_size(padding: u64) -> Option<usize> {
+        let q = T::order() as u128;
+        let mut c = 0;
+        while 256u128.pow(c as u32 + 1) * padding as u128 <= q {
+            c += 1;
+        }
+        if c == 0 {
+            None
+        } else {
+            Some(c)
+        }
+    }
+
+    /// バイト列を点の列にする。
+    ///
+    /// 先頭の点にバイト数を入れ、残りはバイト列を```chunk_size```バイトずつに区切って
+    /// ビッグエンディアンの整数とみなし、```encode_integer```で点にする。
+    pub fn encode_bytes(&self, bytes: &[u8], padding: u64) -> Option<Vec<RationalPoint<T>>> {
+        let c = Self::chunk_size(padding)?;
+        let mut points = vec![self.encode_integer(bytes.len() as u64, padding)?];
+        for chunk in bytes.chunks(c) {
+            let m = chunk.iter().fold(0, |m, &byte| (m << 8) | byte as u64);
+            points.push(self.encode_integer(m << (8 * (c - chunk.len())), padding)?);
+        }
+        Some(points)
+    }
+
+    /// ```encode_bytes```の逆。
+    pub fn decode_bytes(&self, points: &[RationalPoint<T>], padding: u64) -> Option<Vec<u8>> {
+        let c = Self::chunk_size(padding)?;
+        let (length, chunks) = points.split_first()?;
+        let length = self.decode_integer(length, padding)? as usize;
+        if chunks.len() != length.div_ceil(c) {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        for point in chunks {
+            let m = self.decode_integer(point, padding)?;
+            bytes.extend_from_slice(&m.to_be_bytes()[(8 - c)..]);
+        }
+        bytes.truncate(length);
+        Some(bytes)
+    }
+}
+
+impl<T: Field + ToInteger> Domain<T> {
+    /// EC-ElGamal暗号化。平文の点Mを、公開鍵Qと乱数k (1 ≤ k < n) で (C1, C2) = ([k]G, M + [k]Q) にする。
+    pub fn encrypt(
+        &self,
+        public_key: &PublicKey<T>,
+        message: &RationalPoint<T>,
+        k: u64,
+    ) -> Result<Ciphertext<T>, CryptoError> {
+        let curve = self.curve();
+        if k == 0 || k >= self.order() {
+            return Err(CryptoError::InvalidNonce);
+        }
+        if !curve.contains(message) {
+            return Err(CryptoError::InvalidMessage);
+        }
+        let public_key = self.validate_public_key(&public_key.point())?;
+        Ok(Ciphertext {
+            c1: curve.multiply(&self.generator(), k),
+            c2: curve.add(message, &curve.multiply(&public_key.point(), k)),
+        })
+    }
+
+    /// EC-ElGamal復号。M = C2 - [d]C1 。
+    pub fn decrypt(
+        &self,
+        private_key: &PrivateKey,
+        ciphertext: &Ciphertext<T>,
+    ) -> RationalPoint<T> {
+        let curve = self.curve();
+        let shared = curve.multiply(&ciphertext.c1, private_key.value());
+        curve.add(&ciphertext.c2, &-shared)
+    }
+}
@@ -0,0 +1,309 @@
+use crate::arithmetic::{from_u64, is_square, power, primitive_element};
+use crate::elements::{Elements, Order};
+use crate::field::Field;
+use crate::finite_field::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::number_theory::{factorize, lcm};
+use crate::polynomial::Polynomial;
+use crate::rational_point::RationalPoint;
+use num::ToPrimitive;
+use std::fmt;
+
+/// 楕円曲線 y^2 = x^3 + ax + b 。
+/// 係数は型```T```の対象のなす体の元。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct EllipticCurve<T> {
+    a: T,
+    b: T,
+}
+
+impl<T: Copy> EllipticCurve<T> {
+    /// コンストラクタ。y^2 = x^3 + ax + b の a, b を与える。
+    pub fn new(a: T, b: T) -> Self {
+        Self { a, b }
+    }
+
+    /// 係数a
+    pub fn a(&self) -> T {
+        self.a
+    }
+
+    /// 係数b
+    pub fn b(&self) -> T {
+        self.b
+    }
+}
+
+impl<T: Copy + Zero + Eq> EllipticCurve<T> {
+    /// 右辺 x^3 + ax + b を多項式として返す。
+    pub fn polynomial(&self) -> Polynomial<T>
+    where
+        T: Identity,
+    {
+        Polynomial::new(&[self.b, self.a, T::zero(), T::identity()])
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<T: fmt::Display + Copy + Zero + Identity + Eq> fmt::Display for EllipticCurve<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "y^2 = {}", self.polynomial().print_f_of_x())
+    }
+}
+
+impl<T: Field> EllipticCurve<T> {
+    /// 判別式 Δ = -16(4a^3 + 27b^2)
+    pub fn discriminant(&self) -> T {
+        -(from_u64::<T>(16)
+            * (from_u64::<T>(4) * self.a * self.a * self.a + from_u64::<T>(27) * self.b * self.b))
+    }
+
+    /// 判別式が0、すなわち楕円曲線になっていないかどうか。
+    pub fn is_singular(&self) -> bool {
+        self.discriminant() == T::zero()
+    }
+
+    /// j不変量 j = 1728 · 4a^3 / (4a^3 + 27b^2) 。特異な場合は```None```を返す。
+    pub fn j_invariant(&self) -> Option<T> {
+        let four_a_cubed = from_u64::<T>(4) * self.a * self.a * self.a;
+        let denominator = four_a_cubed + from_u64::<T>(27) * self.b * self.b;
+        (from_u64::<T>(1728) * four_a_cubed).checked_div(&denominator)
+    }
+
+    /// j不変量が与えられた値になる曲線を1つ作る。
+    /// j = 0 なら y^2 = x^3 + 1、j = 1728 なら y^2 = x^3 + x、
+    /// それ以外は y^2 = x^3 + 3j(1728 - j)x + 2j(1728 - j)^2 とする。
+    pub fn from_j_invariant(j: T) -> Self {
+        let c = from_u64::<T>(1728) - j;
+        if j == T::zero() {
+            Self::new(T::zero(), T::identity())
+        } else if c == T::zero() {
+            Self::new(T::identity(), T::zero())
+        } else {
+            Self::new(from_u64::<T>(3) * j * c, from_u64::<T>(2) * j * c * c)
+        }
+    }
+
+    /// 点が曲線上にあるかどうか。無限遠点Oは常に曲線上にある。
+    pub fn contains(&self, point: &RationalPoint<T>) -> bool {
+        match *point {
+            RationalPoint::O => true,
+            RationalPoint::Point(x, y) => y * y == x * x * x + self.a * x + self.b,
+        }
+    }
+
+    /// 曲線上の有理点の和 P + Q
+    pub fn add(&self, p: &RationalPoint<T>, q: &RationalPoint<T>) -> RationalPoint<T> {
+        p.add_rational_points(q, self.a)
+    }
+
+    /// 曲線上の有理点のスカラー倍 [n]P
+    pub fn multiply(&self, p: &RationalPoint<T>, n: u64) -> RationalPoint<T> {
+        p.multiply(n, self.a)
+    }
+
+    /// 位数の倍数nが分かっているときに、点の位数を求める。
+    /// nの素因数lについて [n/l]P = O である限りnをlで割っていく。
+    pub fn point_order(&self, point: &RationalPoint<T>, n: u64) -> u64 {
+        let mut order = n;
+        for (l, _) in factorize(n) {
+            while order.is_multiple_of(l) && self.multiply(point, order / l) == RationalPoint::O {
+                order /= l;
+            }
+        }
+        order
+    }
+
+    /// 変数変換 x = u^2 x' + r, y = u^3 y' + s u^2 x' + t によって、
+    /// この曲線上の点 (x, y) を変換先の曲線上の点 (x', y') に移す。
+    pub fn transform_point(
+        point: &RationalPoint<T>,
+        (u, r, s, t): (T, T, T, T),
+    ) -> RationalPoint<T> {
+        match *point {
+            RationalPoint::O => RationalPoint::O,
+            RationalPoint::Point(x, y) => {
+                let u_inv = u.inverse().unwrap();
+                let x_new = (x - r) * u_inv * u_inv;
+                let y_new = (y - s * (x - r) - t) * u_inv * u_inv * u_inv;
+                RationalPoint::Point(x_new, y_new)
+            }
+        }
+    }
+}
+
+impl<T: Field + Order + Elements> EllipticCurve<T> {
+    /// 有理点を全て求める。無限遠点Oを含む。
+    pub fn points(&self) -> Vec<RationalPoint<T>> {
+        let mut res = vec![RationalPoint::O];
+        let elements = T::elements();
+        for &x in &elements {
+            let rhs = x * x * x + self.a * x + self.b;
+            if !is_square(rhs) {
+                continue;
+            }
+            for &y in &elements {
+                if y * y == rhs {
+                    res.push(RationalPoint::Point(x, y));
+                }
+            }
+        }
+        res
+    }
+
+    /// 有理点の個数。各xについて x^3 + ax + b が平方元かどうかを見て数える。
+    pub fn count_points(&self) -> u64 {
+        let mut count = 1;
+        for x in T::elements() {
+            let rhs = x * x * x + self.a * x + self.b;
+            if rhs == T::zero() {
+                count += 1;
+            } else if is_square(rhs) {
+                count += 2;
+            }
+        }
+        count
+    }
+
+    /// 点の位数
+    pub fn order_of_point(&self, point: &RationalPoint<T>) -> u64 {
+        self.point_order(point, self.count_points())
+    }
+
+    /// 有理点のなす群の構造 Z/n1 × Z/n2 (n1 | n2) の (n1, n2) を返す。
+    /// n2 は群の exponent なので、各点の位数の最小公倍数として求める。
+    pub fn group_structure(&self) -> (u64, u64) {
+        let n = self.count_points();
+        let mut exponent = 1;
+        for point in self.points() {
+            exponent = lcm(exponent, self.point_order(&point, n));
+            if exponent == n {
+                break;
+            }
+        }
+        (n / exponent, exponent)
+    }
+
+    /// 他の曲線への同型写像を探す。
+    /// 存在すれば変数変換 x = u^2 x' + r, y = u^3 y' + s u^2 x' + t の (u, r, s, t) を返す。
+    /// どちらも y^2 = x^3 + ax + b の形なので r = s = t = 0 で、a = u^4 a', b = u^6 b' となる u を探せばよい。
+    pub fn isomorphism(&self, other: &Self) -> Option<(T, T, T, T)> {
+        if self.is_singular() || other.is_singular() {
+            return None;
+        }
+        for u in T::elements() {
+            if u == T::zero() {
+                continue;
+            }
+            let u2 = u * u;
+            let u4 = u2 * u2;
+            let u6 = u4 * u2;
+            if self.a == u4 * other.a && self.b == u6 * other.b {
+                return Some((u, T::zero(), T::zero(), T::zero()));
+            }
+        }
+        None
+    }
+
+    /// 同型かどうか。
+    pub fn is_isomorphic(&self, other: &Self) -> bool {
+        self.isomorphism(other).is_some()
+    }
+
+    /// 2次ツイスト y^2 = x^3 + d^2 a x + d^3 b （dは平方非剰余）。
+    pub fn quadratic_twist(&self) -> Self {
+        let d = primitive_element::<T>();
+        Self::new(d * d * self.a, d * d * d * self.b)
+    }
+
+    /// 同型を除いたツイストを全て返す。最初の元は自分自身。
+    ///
+    /// j ≠ 0, 1728 のときは2次ツイスト、
+    /// j = 1728 (b = 0) のときは4次ツイスト y^2 = x^3 + D a x 、
+    /// j = 0 (a = 0) のときは6次ツイスト y^2 = x^3 + D b を並べる。
+    /// ここで D は F_q^* / (F_q^*)^k の代表元。
+    pub fn twists(&self) -> Vec<Self> {
+        let g = primitive_element::<T>();
+        let q = T::order();
+        if self.b == T::zero() {
+            let k = num::Integer::gcd(&4, &(q - 1));
+            (0..k)
+                .map(|i| Self::new(power(g, i) * self.a, T::zero()))
+                .collect()
+        } else if self.a == T::zero() {
+            let k = num::Integer::gcd(&6, &(q - 1));
+            (0..k)
+                .map(|i| Self::new(T::zero(), power(g, i) * self.b))
+                .collect()
+        } else {
+            vec![*self, self.quadratic_twist()]
+        }
+    }
+
+    /// 各ツイストとその有理点の個数の組を返す。
+    pub fn twist_point_counts(&self) -> Vec<(Self, u64)> {
+        self.twists()
+            .into_iter()
+            .map(|e| (e, e.count_points()))
+            .collect()
+    }
+
+    /// Frobenius写像のトレース t = q + 1 - #E(F_q)
+    pub fn frobenius_trace(&self) -> i64 {
+        T::order() as i64 + 1 - self.count_points() as i64
+    }
+}
+
+/// ```hasse_invariant```で扱う標数の上限。x^(p - 1) の係数を求める和はおよそ p/12 項になる。
+pub const HASSE_INVARIANT_LIMIT: u64 = 1 << 20;
+
+impl<T: FiniteField> EllipticCurve<T> {
+    /// Hasse不変量。(x^3 + ax + b)^((p - 1)/2) の x^(p - 1) の係数。
+    ///
+    /// 多項式の冪は作らずに、展開した x^(3i) (ax)^j b^k (i + j + k = (p - 1)/2) の項のうち
+    /// 3i + j = p - 1 となるものだけを多項係数をかけて足す。
+    /// 項数が p に比例するので、pが```HASSE_INVARIANT_LIMIT```より大きい場合は```None```を返す。
+    pub fn hasse_invariant(&self) -> Option<T> {
+        let p = T::characteristic()
+            .to_u64()
+            .filter(|&p| p <= HASSE_INVARIANT_LIMIT)?;
+        let m = (p - 1) / 2;
+        // k = 2i - m ≥ 0 と j = p - 1 - 3i ≥ 0 から i の範囲が決まる
+        let (first, last) = (m.div_ceil(2), (p - 1) / 3);
+        if p < 5 || first > last {
+            return Some(T::zero());
+        }
+        let (mut j, mut k) = (p - 1 - 3 * first, 2 * first - m);
+        // 多項係数 m! / (i! j! k!) 。m < p なので分母は0にならない
+        let factorial = |n: u64| (1..=n).fold(T::identity(), |acc, l| acc * from_u64::<T>(l));
+        let mut c = factorial(m) / (factorial(first) * factorial(j) * factorial(k));
+        let mut sum = T::zero();
+        for i in first..=last {
+            sum += c * power(self.a, j) * power(self.b, k);
+            if i < last {
+                // iを1増やすと多項係数は j(j - 1)(j - 2) / ((i + 1)(k + 1)(k + 2)) 倍になる。
+                // p ≤ 2^20 なので3つの積は```u64```に収まる
+                c = c * from_u64::<T>(j * (j - 1) * (j - 2))
+                    / from_u64::<T>((i + 1) * (k + 1) * (k + 2));
+                j -= 3;
+                k += 2;
+            }
+        }
+        Some(sum)
+    }
+
+    /// 超特異かどうか。Hasse不変量が0になることで判定する。
+    /// Hasse不変量が計算できない場合は```None```を返す。
+    pub fn is_supersingular(&self) -> Option<bool> {
+        Some(self.hasse_invariant()? == T::zero())
+    }
+}
+
+/// 超特異なj不変量を全て求める。
+/// 超特異なj不変量は全て F_p^2 に属するので、F_p^2 上で探せば全て見つかる。
+pub fn supersingular_j_invariants<T: FiniteField + Elements>() -> Vec<T> {
+    T::elements()
+        .into_iter()
+        .filter(|&j| EllipticCurve::from_j_invariant(j).is_supersingular() == Some(true))
+        .collect()
+}
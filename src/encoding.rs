@@ -0,0 +1,96 @@
+use crate::elliptic_curve::EllipticCurve;
+use crate::finite_field::FiniteField;
+use crate::rational_point::RationalPoint;
+use crate::sign::Sign;
+
+/// 無限遠点 O を表すバイト
+const INFINITY: u8 = 0x00;
+/// 圧縮した点で、yの符号が```false```のもの
+const COMPRESSED_EVEN: u8 = 0x02;
+/// 圧縮した点で、yの符号が```true```のもの
+const COMPRESSED_ODD: u8 = 0x03;
+/// 圧縮していない点
+const UNCOMPRESSED: u8 = 0x04;
+
+/// 有限体の元の固定長のバイト列表現。
+///
+/// ```ModInt<P>```は代表元 (0 以上 P 未満) を P - 1 が入る最小のバイト数のビッグエンディアンで表す。
+/// ```Complex<ModInt<P>>```の元 a + bi は a のバイト列の後に b のバイト列を続けたもの。
+/// どちらも表現は一意で、範囲外の値を含むバイト列は受け付けない。
+pub trait ByteEncoding
+where
+    Self: std::marker::Sized,
+{
+    /// バイト列の長さ
+    fn byte_length() -> usize;
+    fn to_bytes(&self) -> Vec<u8>;
+    /// 長さが違うか、正規の表現でない場合は```None```を返す。
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<T: ByteEncoding + Sign> RationalPoint<T> {
+    /// SEC1にならった圧縮しない表現。O は 0x00 、それ以外は 0x04 || x || y 。
+    pub fn to_uncompressed_bytes(&self) -> Vec<u8> {
+        match self {
+            RationalPoint::O => vec![INFINITY],
+            RationalPoint::Point(x, y) => {
+                let mut bytes = vec![UNCOMPRESSED];
+                bytes.extend(x.to_bytes());
+                bytes.extend(y.to_bytes());
+                bytes
+            }
+        }
+    }
+
+    /// SEC1にならった圧縮した表現。O は 0x00 、それ以外は yの符号（```Sign```）が
+    /// ```false```なら 0x02 、```true```なら 0x03 を先頭にして x を続ける。
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        match self {
+            RationalPoint::O => vec![INFINITY],
+            RationalPoint::Point(x, y) => {
+                let mut bytes = vec![if y.sign() {
+                    COMPRESSED_ODD
+                } else {
+                    COMPRESSED_EVEN
+                }];
+                bytes.extend(x.to_bytes());
+                bytes
+            }
+        }
+    }
+}
+
+impl<T: FiniteField + Sign + ByteEncoding> EllipticCurve<T> {
+    /// ```to_uncompressed_bytes```と```to_compressed_bytes```の逆。
+    /// 圧縮した表現では x^3 + ax + b の平方根のうち符号の合う方をyとする。
+    /// 形式が正しくない場合や、点が曲線上にない場合は```None```を返す。
+    pub fn point_from_bytes(&self, bytes: &[u8]) -> Option<RationalPoint<T>> {
+        let n = T::byte_length();
+        let (&tag, rest) = bytes.split_first()?;
+        match tag {
+            INFINITY if rest.is_empty() => Some(RationalPoint::O),
+            UNCOMPRESSED if rest.len() == 2 * n => {
+                let point =
+                    RationalPoint::Point(T::from_bytes(&rest[..n])?, T::from_bytes(&rest[n..])?);
+                if self.contains(&point) {
+                    Some(point)
+                } else {
+                    None
+                }
+            }
+            COMPRESSED_EVEN | COMPRESSED_ODD if rest.len() == n => {
+                let x = T::from_bytes(rest)?;
+                let y = (x * x * x + self.a() * x + self.b()).square_root()?;
+                let sign = tag == COMPRESSED_ODD;
+                if y.sign() == sign {
+                    Some(RationalPoint::Point(x, y))
+                } else if y != T::zero() {
+                    Some(RationalPoint::Point(x, -y))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::arithmetic::{is_square, power};
+use crate::elements::Order;
+use crate::finite_field::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::inverse::Inverse;
+use crate::number_theory::factorize;
+use crate::polynomial::Polynomial;
+use crate::random::{Random, Rng};
+use crate::square_root::SquareRoot;
+use num::traits::CheckedDiv;
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 有限体 F_q の K 次拡大体 F_q^K = F_q\[x\]/(x^K - c) の元。ここで F_q は型```T```の対象のなす体。
+///
+/// c は x^K - c が既約になる F_q の元で、```modulus```が型と K ごとに1回だけ探す。
+/// 位数 q^K が```u64```に収まることを想定している。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ExtensionField<T, const K: usize> {
+    coefficients: [T; K],
+}
+
+thread_local! {
+    /// ```modulus```が見つけた c 。(```T```の```TypeId```, K) ごとに覚えておく。
+    static MODULI: RefCell<HashMap<(TypeId, usize), Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// x^k - c が既約になる c を探す。
+///
+/// kの素因数 l がすべて q - 1 を割り切り、4 | k なら q ≡ 1 mod 4 のとき、
+/// c が F_q でどの l 乗元にもならないこと（c^((q - 1)/l) ≠ 1）が既約になる条件。
+/// 条件を満たす c がない場合は```None```を返す。
+fn find_modulus<T: FiniteField + Order>(k: usize) -> Option<T> {
+    let q = T::order();
+    let primes: Vec<u64> = factorize(k as u64).iter().map(|&(l, _)| l).collect();
+    if primes.iter().any(|&l| !(q - 1).is_multiple_of(l)) || (k.is_multiple_of(4) && q % 4 != 1) {
+        return None;
+    }
+    // F_q の原始元は条件を満たすので、いずれ見つかる
+    let mut rng = Rng::new(k as u64);
+    loop {
+        let c = T::random(&mut rng);
+        if c != T::zero()
+            && primes
+                .iter()
+                .all(|&l| power(c, (q - 1) / l) != T::identity())
+        {
+            return Some(c);
+        }
+    }
+}
+
+impl<T: Copy + Zero, const K: usize> ExtensionField<T, K> {
+    /// コンストラクタ。i番目がx^iの係数。
+    pub fn new(coefficients: [T; K]) -> Self {
+        Self { coefficients }
+    }
+
+    /// F_q の元を定数として埋め込む。
+    pub fn embed(t: T) -> Self {
+        let mut coefficients = [T::zero(); K];
+        coefficients[0] = t;
+        Self { coefficients }
+    }
+
+    /// 係数を返す。i番目がx^iの係数。
+    pub fn coefficients(&self) -> [T; K] {
+        self.coefficients
+    }
+}
+
+impl<T: Copy + Zero + Eq, const K: usize> ExtensionField<T, K> {
+    /// 次数がK未満のxの多項式で表す。
+    pub fn to_polynomial(&self) -> Polynomial<T> {
+        Polynomial::new(&self.coefficients)
+    }
+
+    /// 次数がK未満の多項式から元を作る。次数がK以上の場合は```None```を返す。
+    pub fn from_polynomial(f: &Polynomial<T>) -> Option<Self> {
+        if f.strict_deg().is_some_and(|d| d >= K) {
+            return None;
+        }
+        Some(Self::new(std::array::from_fn(|i| f.coefficient(i))))
+    }
+}
+
+impl<T: FiniteField + Order + 'static, const K: usize> ExtensionField<T, K> {
+    /// x^K - c が既約になる c 。そのような c がない場合は```None```。
+    pub fn modulus() -> Option<T> {
+        let key = (TypeId::of::<T>(), K);
+        let cached = MODULI.with(|moduli| {
+            moduli
+                .borrow()
+                .get(&key)
+                .and_then(|c| c.downcast_ref::<Option<T>>().copied())
+        });
+        if let Some(c) = cached {
+            return c;
+        }
+        let c = find_modulus::<T>(K);
+        MODULI.with(|moduli| moduli.borrow_mut().insert(key, Box::new(c)));
+        c
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+/// xの多項式として表示する。
+impl<T: fmt::Display + Zero + Identity + Eq + Copy, const K: usize> fmt::Display
+    for ExtensionField<T, K>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_polynomial())
+    }
+}
+
+impl<T: Copy + Add<Output = T>, const K: usize> Add for ExtensionField<T, K> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            coefficients: std::array::from_fn(|i| self.coefficients[i] + rhs.coefficients[i]),
+        }
+    }
+}
+
+impl<T: Copy + Add<Output = T>, const K: usize> AddAssign for ExtensionField<T, K> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const K: usize> Sub for ExtensionField<T, K> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            coefficients: std::array::from_fn(|i| self.coefficients[i] - rhs.coefficients[i]),
+        }
+    }
+}
+
+impl<T: Copy + Sub<Output = T>, const K: usize> SubAssign for ExtensionField<T, K> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// x^K = c で次数を下げる。c は次数がK以上になる項が出たときだけ```modulus```から取り出す。
+impl<T: FiniteField + Order + 'static, const K: usize> Mul for ExtensionField<T, K> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut coefficients = [T::zero(); K];
+        let mut modulus = None;
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            if a == T::zero() {
+                continue;
+            }
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                if i + j < K {
+                    coefficients[i + j] += a * b;
+                } else {
+                    let c = *modulus.get_or_insert_with(|| {
+                        Self::modulus().expect("x^K - c の形の既約多項式がありません。")
+                    });
+                    coefficients[i + j - K] += c * a * b;
+                }
+            }
+        }
+        Self { coefficients }
+    }
+}
+
+impl<T: FiniteField + Order + 'static, const K: usize> MulAssign for ExtensionField<T, K> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// ノルム N(a) = a a^q a^(q^2) ... a^(q^(K-1)) は F_q に入るので、
+/// a^(-1) = a^q a^(q^2) ... a^(q^(K-1)) N(a)^(-1) 。0の場合は```None```を返す。
+impl<T: FiniteField + Order + 'static, const K: usize> Inverse for ExtensionField<T, K> {
+    fn inverse(self) -> Option<Self> {
+        let mut conjugate = self;
+        let mut product = Self::identity();
+        for _ in 1..K {
+            conjugate = power(conjugate, T::order());
+            product *= conjugate;
+        }
+        let norm = (self * product).coefficients[0].inverse()?;
+        Some(product * Self::embed(norm))
+    }
+}
+
+/// 逆元を掛ける。0で割るとpanicする。
+impl<T: FiniteField + Order + 'static, const K: usize> Div for ExtensionField<T, K> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs
+            .inverse()
+            .expect("逆元が存在しない元で割ることはできません。")
+    }
+}
+
+impl<T: FiniteField + Order + 'static, const K: usize> DivAssign for ExtensionField<T, K> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+/// 割り算。0で割る場合は```None```を返す。
+impl<T: FiniteField + Order + 'static, const K: usize> CheckedDiv for ExtensionField<T, K> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        Some(*self * v.inverse()?)
+    }
+}
+
+impl<T: Copy + Neg<Output = T>, const K: usize> Neg for ExtensionField<T, K> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            coefficients: std::array::from_fn(|i| -self.coefficients[i]),
+        }
+    }
+}
+
+impl<T: Copy + Zero, const K: usize> Zero for ExtensionField<T, K> {
+    fn zero() -> Self {
+        Self {
+            coefficients: [T::zero(); K],
+        }
+    }
+}
+
+impl<T: Copy + Zero + Identity, const K: usize> Identity for ExtensionField<T, K> {
+    fn identity() -> Self {
+        Self::embed(T::identity())
+    }
+}
+
+/// 位数 q^K 。```u64```に収まらない場合はパニックする。
+impl<T: Order, const K: usize> Order for ExtensionField<T, K> {
+    fn order() -> u64 {
+        T::order()
+            .checked_pow(K as u32)
+            .expect("F_q^K の位数が u64 に収まりません。")
+    }
+}
+
+/// Tonelli–Shanks法による平方根。q は奇数であることを想定している。
+impl<T: FiniteField + Order + 'static, const K: usize> SquareRoot for ExtensionField<T, K> {
+    fn square_root(self) -> Option<Self> {
+        if self == Self::zero() {
+            return Some(self);
+        }
+        if !is_square(self) {
+            return None;
+        }
+        // q^K - 1 = m 2^s (mは奇数)
+        let order = Self::order() - 1;
+        let s = order.trailing_zeros();
+        let m = order >> s;
+        let mut rng = Rng::new(0);
+        let z = loop {
+            let z = Self::random(&mut rng);
+            if !is_square(z) {
+                break z;
+            }
+        };
+        let mut s = s;
+        let mut c = power(z, m);
+        let mut t = power(self, m);
+        let mut r = power(self, m.div_ceil(2));
+        while t != Self::identity() {
+            // t^(2^i) = 1 となる最小の i
+            let mut i = 0;
+            let mut t2 = t;
+            while t2 != Self::identity() {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            if i == s {
+                return None;
+            }
+            let b = power(c, 1 << (s - i - 1));
+            s = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+        if r * r == self {
+            Some(r)
+        } else {
+            None
+        }
+    }
+}
+
+/// 係数を独立に一様にとる。
+impl<T: Random, const K: usize> Random for ExtensionField<T, K> {
+    fn random(rng: &mut Rng) -> Self {
+        Self {
+            coefficients: std::array::from_fn(|_| T::random(rng)),
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::inverse::Inverse;
+use crate::ring::Ring;
+use num::traits::CheckedDiv;
+use std::ops::{Div, DivAssign};
+
+/// 体。0 以外の元が逆元を持つ環。逆元を掛ける割り算```/```と、割れない場合に```None```を返す```checked_div```を持つ。
+/// 条件を満たす型には自動で実装される。
+pub trait Field: Ring + Inverse + Div<Output = Self> + DivAssign + CheckedDiv {}
+
+impl<T: Ring + Inverse + Div<Output = T> + DivAssign + CheckedDiv> Field for T {}
@@ -0,0 +1,19 @@
+use crate::characteristic::Characteristic;
+use crate::field::Field;
+use crate::random::Random;
+use crate::square_root::SquareRoot;
+use num::BigUint;
+
+/// 有限体。標数、位数、Frobenius写像、一様乱数、平方根を与える。
+pub trait FiniteField: Field + Characteristic + SquareRoot + Random {
+    /// 素体 F_p 上の拡大次数
+    fn degree() -> u32;
+
+    /// Frobenius写像 x ↦ x^p
+    fn frobenius(self) -> Self;
+
+    /// 位数 p^degree 。```Order```と違い、```u64```に収まらない場合も扱える。
+    fn cardinality() -> BigUint {
+        Self::characteristic().pow(Self::degree())
+    }
+}
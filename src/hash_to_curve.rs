@@ -0,0 +1,108 @@
+use crate::elements::Order;
+use crate::elliptic_curve::EllipticCurve;
+use crate::finite_field::FiniteField;
+use crate::integer::FromInteger;
+use crate::rational_point::RationalPoint;
+use crate::sign::Sign;
+use sha2::{Digest, Sha256};
+
+/// try-and-increment法で試すカウンタの上限。1回あたりの失敗確率はおよそ1/2なので、十分大きい。
+const MAX_TRIES: u32 = 256;
+
+/// SHA-256(DST || DSTの長さ || メッセージ || カウンタ) 。
+/// DST (domain separation tag) はプロトコルごとに異なるハッシュ関数にするための文字列。
+fn digest(message: &[u8], dst: &[u8], counter: u32) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(dst);
+    hasher.update([dst.len() as u8]);
+    hasher.update(message);
+    hasher.update(counter.to_be_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// バイト列を有限体の元にする。ハッシュ値の先頭128ビットを位数で割った余りを```FromInteger```で元にするので、
+/// 位数が```u64```に収まる限り偏りは 2^(-64) 以下。
+pub fn hash_to_field<T: FromInteger + Order>(message: &[u8], dst: &[u8], counter: u32) -> T {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(&digest(message, dst, counter)[..16]);
+    let n = u128::from_be_bytes(buf) % T::order() as u128;
+    T::from_integer(n as u64).unwrap()
+}
+
+impl<T: FiniteField + Sign + FromInteger + Order> EllipticCurve<T> {
+    /// x^3 + ax + b
+    fn right_hand_side(&self, x: T) -> T {
+        x * x * x + self.a() * x + self.b()
+    }
+
+    /// try-and-increment法。カウンタを増やしながらx座標の候補をハッシュで作り、
+    /// x^3 + ax + b が平方元になったところで点にする。yの符号もハッシュ値から決める。
+    ///
+    /// 定数時間ではなく、実行時間からメッセージの情報が漏れるので、比較のための基準として使う。
+    pub fn hash_to_curve_try_and_increment(
+        &self,
+        message: &[u8],
+        dst: &[u8],
+    ) -> Option<RationalPoint<T>> {
+        for counter in 0..MAX_TRIES {
+            let x = hash_to_field::<T>(message, dst, counter);
+            if let Some(y) = self.right_hand_side(x).square_root() {
+                let sign = digest(message, dst, counter)[16] & 1 == 1;
+                let y = if y.sign() == sign { y } else { -y };
+                return Some(RationalPoint::Point(x, y));
+            }
+        }
+        None
+    }
+
+    /// 簡略化したShallue–van de Woestijne–Ulas写像で使う非平方元Z。
+    /// Z ≠ -1 で、g(b / (Za)) が平方元になるもの (g(x) = x^3 + ax + b) を、
+    /// 体を列挙せずにハッシュ値で作った候補から決定的に選ぶ。
+    /// ab = 0 の場合は写像が定義できないので```None```を返す。
+    pub fn swu_parameter(&self) -> Option<T> {
+        if self.a() == T::zero() || self.b() == T::zero() {
+            return None;
+        }
+        (0..MAX_TRIES)
+            .map(|counter| hash_to_field::<T>(b"", b"SWU-Z", counter))
+            .find(|&z| {
+                z != T::zero()
+                    && z != -T::identity()
+                    && z.square_root().is_none()
+                    && self
+                        .right_hand_side(self.b() / (z * self.a()))
+                        .square_root()
+                        .is_some()
+            })
+    }
+
+    /// 簡略化したSWU写像（RFC 9380 6.6.2節）。体の元uを曲線上の点にする。
+    ///
+    /// x1 = (-b/a)(1 + 1/(Z^2 u^4 + Z u^2)) と x2 = Z u^2 x1 について g(x1) g(x2) = Z^3 u^6 g(x1)^2 となり、
+    /// Zは非平方元なので g(x1) と g(x2) のどちらかは平方元になる。yの符号はuの符号に合わせる。
+    pub fn map_to_curve_swu(&self, u: T) -> Option<RationalPoint<T>> {
+        let z = self.swu_parameter()?;
+        let (a, b) = (self.a(), self.b());
+        let tv = z * z * u * u * u * u + z * u * u;
+        let x1 = if tv == T::zero() {
+            b.checked_div(&(z * a))?
+        } else {
+            (-b).checked_div(&a)? * (T::identity() + tv.inverse()?)
+        };
+        let x2 = z * u * u * x1;
+        let (x, y) = match self.right_hand_side(x1).square_root() {
+            Some(y) => (x1, y),
+            None => (x2, self.right_hand_side(x2).square_root()?),
+        };
+        let y = if y.sign() == u.sign() { y } else { -y };
+        Some(RationalPoint::Point(x, y))
+    }
+
+    /// 簡略化したSWU写像によるハッシュ。メッセージから体の元を2つ作り、それぞれの像を足す。
+    /// 2つの像の和をとることで、出力の分布が一様分布に近くなる（RFC 9380 の hash_to_curve ）。
+    pub fn hash_to_curve_swu(&self, message: &[u8], dst: &[u8]) -> Option<RationalPoint<T>> {
+        let p0 = self.map_to_curve_swu(hash_to_field::<T>(message, dst, 0))?;
+        let p1 = self.map_to_curve_swu(hash_to_field::<T>(message, dst, 1))?;
+        Some(self.add(&p0, &p1))
+    }
+}
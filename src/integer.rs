@@ -0,0 +1,12 @@
+/// 有限体の元を 0 以上 位数未満の整数に対応させる。
+pub trait ToInteger {
+    fn to_integer(&self) -> u64;
+}
+
+/// ```ToInteger```の逆。位数以上の整数の場合は```None```を返す。
+pub trait FromInteger
+where
+    Self: std::marker::Sized,
+{
+    fn from_integer(n: u64) -> Option<Self>;
+}
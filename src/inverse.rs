@@ -1,3 +1,5 @@
+use crate::field::Field;
+
 /// 逆元を与える。可逆元の場合は```Some(逆元)```を返し、そうでない場合は```None```を返す
 pub trait Inverse
 where
@@ -5,3 +7,33 @@ where
 {
     fn inverse(self) -> Option<Self>;
 }
+
+/// Montgomeryの方法で、スライスの元の逆元をまとめて求める。
+///
+/// 累積積 c_i = x_1 x_2 ... x_i を作り、全体の積の逆元を1回だけ計算してから、
+/// 後ろから x_i^(-1) = c_(i-1) c_i^(-1) 、c_(i-1)^(-1) = x_i c_i^(-1) とたどる。
+/// 0の元は```None```にして積から除く。残りの積が可逆でない場合は1つずつ逆元を計算する。
+pub fn batch_inverse<T: Field>(values: &[T]) -> Vec<Option<T>> {
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut product = T::identity();
+    for &x in values {
+        if x != T::zero() {
+            product *= x;
+        }
+        prefix.push(product);
+    }
+    let mut inverse = match product.inverse() {
+        Some(inverse) => inverse,
+        None => return values.iter().map(|&x| x.inverse()).collect(),
+    };
+    let mut result = vec![None; values.len()];
+    for i in (0..values.len()).rev() {
+        if values[i] == T::zero() {
+            continue;
+        }
+        let before = if i == 0 { T::identity() } else { prefix[i - 1] };
+        result[i] = Some(before * inverse);
+        inverse *= values[i];
+    }
+    result
+}
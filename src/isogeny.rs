@@ -0,0 +1,220 @@
+use crate::arithmetic::from_u64;
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::identities::{Identity, Zero};
+use crate::polynomial::Polynomial;
+use crate::rational_point::RationalPoint;
+use crate::ring::Ring;
+use std::fmt;
+
+/// Véluの公式で与えられる同種写像 φ: E → E' 。
+///
+/// 核多項式 D(x) = Π (x - x_Q) （Qは核の O 以外の点を ±Q で同一視したもの）を用いて、
+/// φ(x, y) = (N(x) / D(x)^2, y · (N'(x)D(x) - 2N(x)D'(x)) / D(x)^3) と表す。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Isogeny<T> {
+    domain: EllipticCurve<T>,
+    codomain: EllipticCurve<T>,
+    kernel_polynomial: Polynomial<T>,
+    numerator: Polynomial<T>,
+    degree: u64,
+}
+
+impl<T: Copy> Isogeny<T> {
+    /// 定義域の曲線 E
+    pub fn domain(&self) -> EllipticCurve<T> {
+        self.domain
+    }
+
+    /// 値域の曲線 E'
+    pub fn codomain(&self) -> EllipticCurve<T> {
+        self.codomain
+    }
+
+    /// 核多項式 D(x)
+    pub fn kernel_polynomial(&self) -> &Polynomial<T> {
+        &self.kernel_polynomial
+    }
+
+    /// x座標の有理関数 N(x) / D(x)^2 の分子 N(x)
+    pub fn numerator(&self) -> &Polynomial<T> {
+        &self.numerator
+    }
+
+    /// 次数（核の位数）
+    pub fn degree(&self) -> u64 {
+        self.degree
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<T: fmt::Display + Copy + Zero + Identity + Eq> fmt::Display for Isogeny<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}次の同種写像 ({}) → ({}), 核多項式 {}",
+            self.degree,
+            self.domain,
+            self.codomain,
+            self.kernel_polynomial.print_f_of_x()
+        )
+    }
+}
+
+/// 多項式の根についての冪和 (Σx_Q, Σx_Q^2, Σx_Q^3) を係数から求める。
+fn power_sums<T: Ring>(d: &Polynomial<T>) -> (T, T, T) {
+    let n = d.deg();
+    let c = |k: usize| match n.checked_sub(k) {
+        Some(i) => d.coefficient(i),
+        None => T::zero(),
+    };
+    let s1 = -c(1);
+    let s2 = c(2);
+    let s3 = -c(3);
+    let p1 = s1;
+    let p2 = s1 * s1 - (s2 + s2);
+    let p3 = s1 * s1 * s1 - (s1 * s2 + s1 * s2 + s1 * s2) + (s3 + s3 + s3);
+    (p1, p2, p3)
+}
+
+impl<T: Field> Isogeny<T> {
+    /// 核多項式 D(x) から同種写像を作る。
+    /// D(x) が有限部分群の核多項式になっていることは仮定する。Dが0なら```None```を返す。
+    ///
+    /// 位数2でない核の点Qについて v_Q = 2(3x_Q^2 + a), u_Q = 4y_Q^2、
+    /// 位数2の点について v_Q = 3x_Q^2 + a, u_Q = 0 とおくと、
+    /// v = Σv_Q, w = Σ(u_Q + x_Q v_Q) として E': y^2 = x^3 + (a - 5v)x + (b - 7w) となる。
+    /// 位数2の点の寄与はちょうど半分になるので、D2 = gcd(D, x^3 + ax + b) の根の分を半分引けばよい。
+    pub fn from_kernel_polynomial(curve: &EllipticCurve<T>, d: &Polynomial<T>) -> Option<Self> {
+        d.strict_deg()?;
+        let d = Polynomial::monic(d);
+        let f = curve.polynomial();
+        let d2 = Polynomial::gcd(&d, &f);
+        let half = from_u64::<T>(2).inverse().unwrap();
+        let (a, b) = (curve.a(), curve.b());
+
+        // 位数2でないとみなしたときの v, w
+        let v_and_w = |g: &Polynomial<T>| {
+            let n = from_u64::<T>(g.deg() as u64);
+            let (p1, p2, p3) = power_sums(g);
+            let v = from_u64::<T>(6) * p2 + from_u64::<T>(2) * a * n;
+            let w = from_u64::<T>(10) * p3 + from_u64::<T>(6) * a * p1 + from_u64::<T>(4) * b * n;
+            (v, w)
+        };
+        let (v1, w1) = v_and_w(&d);
+        let (v2, w2) = if d2.deg() == 0 {
+            (T::zero(), T::zero())
+        } else {
+            v_and_w(&d2)
+        };
+        let v = v1 - half * v2;
+        let w = w1 - half * w2;
+        let codomain = EllipticCurve::new(a - from_u64::<T>(5) * v, b - from_u64::<T>(7) * w);
+
+        let x = Polynomial::new(&[T::zero(), T::identity()]);
+        let (quotient, _) = Polynomial::div_rem(&d, &d2);
+        let numerator = x * d.clone() * d.clone() + Self::velu_sum(&d, &f)
+            - Polynomial::new_constant(half)
+                * Self::velu_sum(&d2, &f)
+                * quotient.clone()
+                * quotient;
+
+        Some(Self {
+            domain: *curve,
+            codomain,
+            kernel_polynomial: d.clone(),
+            numerator,
+            degree: 1 + 2 * d.deg() as u64 - d2.deg() as u64,
+        })
+    }
+
+    /// D(x)^2 Σ ( 2f'(x_Q)/(x - x_Q) + 4f(x_Q)/(x - x_Q)^2 ) を x の多項式として求める。
+    /// f(x_Q), f'(x_Q) を x のまわりで展開すると
+    /// 2(dx - Σx_Q)D^2 - 2f'D'D + 4f(D'^2 - DD'') になる。
+    fn velu_sum(d: &Polynomial<T>, f: &Polynomial<T>) -> Polynomial<T> {
+        if d.deg() == 0 {
+            return Polynomial::zero();
+        }
+        let n = from_u64::<T>(d.deg() as u64);
+        let two = from_u64::<T>(2);
+        let four = from_u64::<T>(4);
+        let d1 = Polynomial::derivative(d);
+        let dd = Polynomial::derivative(&d1);
+        let f1 = Polynomial::derivative(f);
+        let (p1, _, _) = power_sums(d);
+        let linear = Polynomial::new(&[-(two * p1), two * n]);
+        linear * d.clone() * d.clone() - Polynomial::new_constant(two) * f1 * d1.clone() * d.clone()
+            + Polynomial::new_constant(four) * f.clone() * (d1.clone() * d1 - d.clone() * dd)
+    }
+
+    /// 核の点を並べたものから同種写像を作る。Oや ±Q の両方が含まれていてもよい。
+    /// 曲線上にない点が含まれているか、足し算で閉じていない場合は```None```を返す。
+    pub fn from_kernel_points(
+        curve: &EllipticCurve<T>,
+        kernel: &[RationalPoint<T>],
+    ) -> Option<Self> {
+        for p in kernel {
+            if !curve.contains(p) {
+                return None;
+            }
+            for q in kernel {
+                let r = curve.add(p, q);
+                if r != RationalPoint::O && !kernel.contains(&r) && !kernel.contains(&-r) {
+                    return None;
+                }
+            }
+        }
+        let mut xs: Vec<T> = Vec::new();
+        for p in kernel {
+            if let RationalPoint::Point(x, _) = *p {
+                if !xs.contains(&x) {
+                    xs.push(x);
+                }
+            }
+        }
+        let mut d = Polynomial::new_constant(T::identity());
+        for x in xs {
+            d *= Polynomial::new(&[-x, T::identity()]);
+        }
+        Self::from_kernel_polynomial(curve, &d)
+    }
+
+    /// 点Pの生成する巡回部分群を核とする同種写像を作る。Pが曲線上にない場合は```None```を返す。
+    pub fn from_kernel_generator(curve: &EllipticCurve<T>, p: &RationalPoint<T>) -> Option<Self> {
+        if !curve.contains(p) {
+            return None;
+        }
+        let mut kernel = vec![*p];
+        let mut q = curve.add(p, p);
+        while q != RationalPoint::O && q != *p {
+            kernel.push(q);
+            q = curve.add(&q, p);
+        }
+        Self::from_kernel_points(curve, &kernel)
+    }
+
+    /// 有理点を移す。核の点は O に移る。
+    pub fn evaluate(&self, point: &RationalPoint<T>) -> RationalPoint<T> {
+        match *point {
+            RationalPoint::O => RationalPoint::O,
+            RationalPoint::Point(x, y) => {
+                let d = Polynomial::evaluate(&self.kernel_polynomial, x);
+                match d.inverse() {
+                    None => RationalPoint::O,
+                    Some(d_inv) => {
+                        let n = Polynomial::evaluate(&self.numerator, x);
+                        let n1 = Polynomial::evaluate(&Polynomial::derivative(&self.numerator), x);
+                        let d1 = Polynomial::evaluate(
+                            &Polynomial::derivative(&self.kernel_polynomial),
+                            x,
+                        );
+                        let x_new = n * d_inv * d_inv;
+                        let y_new =
+                            y * (n1 * d - from_u64::<T>(2) * n * d1) * d_inv * d_inv * d_inv;
+                        RationalPoint::Point(x_new, y_new)
+                    }
+                }
+            }
+        }
+    }
+}
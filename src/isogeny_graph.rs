@@ -0,0 +1,240 @@
+use crate::elements::{Elements, Order};
+use crate::elliptic_curve::{supersingular_j_invariants, EllipticCurve};
+use crate::finite_field::FiniteField;
+use crate::isogeny::Isogeny;
+use crate::number_theory::is_prime;
+use crate::rational_point::RationalPoint;
+use num::ToPrimitive;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// 超特異ℓ-同種写像グラフ。
+///
+/// 頂点は超特異なj不変量、各頂点から出る辺はℓ次の部分群ごとに1本ずつ（全部でℓ + 1本）。
+/// 多重辺やループもそのまま残す。
+#[derive(Clone, Debug)]
+pub struct IsogenyGraph<T> {
+    l: u64,
+    vertices: Vec<T>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl<T> IsogenyGraph<T> {
+    /// ℓ
+    pub fn l(&self) -> u64 {
+        self.l
+    }
+
+    /// 頂点（j不変量）を並べたもの
+    pub fn vertices(&self) -> &Vec<T> {
+        &self.vertices
+    }
+
+    /// 隣接リスト。i番目の頂点から出る辺の行き先の番号を並べたもの。
+    pub fn edges(&self) -> &Vec<Vec<usize>> {
+        &self.edges
+    }
+
+    /// 頂点の個数
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// 頂点iからの（辺の向きに沿った）距離を幅優先探索で求める。たどり着けない頂点は```None```。
+    fn distances_from(&self, i: usize) -> Vec<Option<usize>> {
+        let mut dist = vec![None; self.vertices.len()];
+        let mut queue = VecDeque::new();
+        dist[i] = Some(0);
+        queue.push_back(i);
+        while let Some(v) = queue.pop_front() {
+            for &w in &self.edges[v] {
+                if dist[w].is_none() {
+                    dist[w] = Some(dist[v].unwrap() + 1);
+                    queue.push_back(w);
+                }
+            }
+        }
+        dist
+    }
+
+    /// 連結かどうか。
+    pub fn is_connected(&self) -> bool {
+        (0..self.vertices.len()).all(|i| self.distances_from(i).iter().all(|d| d.is_some()))
+    }
+
+    /// 直径。連結でない場合は```None```を返す。
+    pub fn diameter(&self) -> Option<usize> {
+        let mut diameter = 0;
+        for i in 0..self.vertices.len() {
+            for d in self.distances_from(i) {
+                diameter = diameter.max(d?);
+            }
+        }
+        Some(diameter)
+    }
+
+    /// 隣接行列。(i, j) 成分は頂点iから頂点jへの辺の本数。
+    pub fn adjacency_matrix(&self) -> Vec<Vec<u64>> {
+        let n = self.vertices.len();
+        let mut matrix = vec![vec![0; n]; n];
+        for (i, row) in self.edges.iter().enumerate() {
+            for &j in row {
+                matrix[i][j] += 1;
+            }
+        }
+        matrix
+    }
+
+    /// 隣接行列の固有値を大きい順に並べたもの。
+    ///
+    /// j = 0, 1728 があると隣接行列は対称にならないが、自己同型の個数で重みをつけると対称になるので、
+    /// 相似な対称行列 (sqrt(A_ij A_ji)) の固有値をJacobi法で求める。
+    #[allow(clippy::needless_range_loop)]
+    pub fn eigenvalues(&self) -> Vec<f64> {
+        let a = self.adjacency_matrix();
+        let n = a.len();
+        let mut s: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| ((a[i][j] * a[j][i]) as f64).sqrt())
+                    .collect()
+            })
+            .collect();
+        for _ in 0..100 {
+            let mut off = 0.0;
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    off += s[i][j] * s[i][j];
+                }
+            }
+            if off < 1e-20 {
+                break;
+            }
+            for p in 0..n {
+                for q in (p + 1)..n {
+                    if s[p][q].abs() < 1e-15 {
+                        continue;
+                    }
+                    let theta = (s[q][q] - s[p][p]) / (2.0 * s[p][q]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let sn = t * c;
+                    for k in 0..n {
+                        let skp = s[k][p];
+                        let skq = s[k][q];
+                        s[k][p] = c * skp - sn * skq;
+                        s[k][q] = sn * skp + c * skq;
+                    }
+                    for k in 0..n {
+                        let spk = s[p][k];
+                        let sqk = s[q][k];
+                        s[p][k] = c * spk - sn * sqk;
+                        s[q][k] = sn * spk + c * sqk;
+                    }
+                }
+            }
+        }
+        let mut eigenvalues: Vec<f64> = (0..n).map(|i| s[i][i]).collect();
+        eigenvalues.sort_by(|x, y| y.partial_cmp(x).unwrap());
+        eigenvalues
+    }
+
+    /// スペクトルギャップ。最大固有値 ℓ + 1 と2番目に大きい固有値の差。頂点が1個なら ℓ + 1 とする。
+    pub fn spectral_gap(&self) -> f64 {
+        let eigenvalues = self.eigenvalues();
+        match eigenvalues.get(1) {
+            Some(&second) => eigenvalues[0] - second,
+            None => eigenvalues[0],
+        }
+    }
+}
+
+impl<T: fmt::Display> IsogenyGraph<T> {
+    /// Graphvizで描くためのDOT形式。多重辺はそのまま複数の辺として出力する。
+    pub fn to_dot(&self) -> String {
+        let mut s = String::new();
+        s.push_str(&format!(
+            "digraph supersingular_{}_isogeny_graph {{\n",
+            self.l
+        ));
+        for j in &self.vertices {
+            s.push_str(&format!("    \"{}\";\n", j));
+        }
+        for (i, row) in self.edges.iter().enumerate() {
+            for &k in row {
+                s.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    self.vertices[i], self.vertices[k]
+                ));
+            }
+        }
+        s.push('}');
+        s
+    }
+
+    /// 隣接リスト形式。1行に1頂点で、「j: 行き先, 行き先, ...」と並べる。
+    pub fn to_adjacency_list(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for (i, row) in self.edges.iter().enumerate() {
+            let neighbours: Vec<String> =
+                row.iter().map(|&k| self.vertices[k].to_string()).collect();
+            lines.push(format!("{}: {}", self.vertices[i], neighbours.join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+impl<T: FiniteField + Order + Elements> IsogenyGraph<T> {
+    /// F_p^2 上の超特異ℓ-同種写像グラフを作る。
+    ///
+    /// 各j不変量について、ツイストの中から #E(F_p^2) = (p ± 1)^2 となるものを選ぶと
+    /// E(F_p^2) ⊇ E[ℓ] となるので、ℓ + 1 個の位数ℓの部分群が全て有理点で書ける。
+    /// そのためℓは p + 1 か p - 1 を割り切る素数である必要があり、そうでないときは```None```を返す。
+    pub fn supersingular(l: u64) -> Option<Self> {
+        let p = T::characteristic().to_u64()?;
+        if !is_prime(l) {
+            return None;
+        }
+        let target = if (p + 1).is_multiple_of(l) {
+            (p + 1) * (p + 1)
+        } else if (p - 1).is_multiple_of(l) {
+            (p - 1) * (p - 1)
+        } else {
+            return None;
+        };
+        let vertices = supersingular_j_invariants::<T>();
+        let mut edges: Vec<Vec<usize>> = Vec::new();
+        for &j in &vertices {
+            let curve = EllipticCurve::from_j_invariant(j)
+                .twists()
+                .into_iter()
+                .find(|e| e.count_points() == target)?;
+            let mut row: Vec<usize> = Vec::new();
+            for phi in Self::isogenies_of_degree(&curve, l) {
+                let j2 = phi.codomain().j_invariant()?;
+                row.push(vertices.iter().position(|&v| v == j2)?);
+            }
+            edges.push(row);
+        }
+        Some(Self { l, vertices, edges })
+    }
+
+    /// 位数ℓの有理点が生成する部分群ごとに、Véluの公式で同種写像を作る。
+    fn isogenies_of_degree(curve: &EllipticCurve<T>, l: u64) -> Vec<Isogeny<T>> {
+        let mut used: Vec<RationalPoint<T>> = Vec::new();
+        let mut isogenies = Vec::new();
+        for point in curve.points() {
+            if point == RationalPoint::O
+                || curve.multiply(&point, l) != RationalPoint::O
+                || used.contains(&point)
+            {
+                continue;
+            }
+            for k in 1..l {
+                used.push(curve.multiply(&point, k));
+            }
+            isogenies.push(Isogeny::from_kernel_generator(curve, &point).unwrap());
+        }
+        isogenies
+    }
+}
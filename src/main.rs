@@ -1,18 +1,52 @@
-const P: u64 = 7; // ここを適宜書き換える。2,3以外の素数で、かつ4で割って3余るものである必要がある。
+// ここを適宜書き換える。引数以上で、2,3以外かつ4で割って3余る最小の素数がpになる。
+const P: u64 = number_theory::next_prime_3_mod_4(7);
 
+pub mod arithmetic;
+pub mod big_modint;
 pub mod characteristic;
+pub mod classification;
 pub mod complexification;
+pub mod coordinates;
+pub mod crypto;
+pub mod discrete_log;
+pub mod ecm;
+pub mod elements;
+pub mod elgamal;
+pub mod elliptic_curve;
+pub mod encoding;
+pub mod extension_field;
+pub mod field;
+pub mod finite_field;
+pub mod hash_to_curve;
 pub mod identities;
+pub mod integer;
 pub mod inverse;
+pub mod isogeny;
+pub mod isogeny_graph;
 pub mod modint;
+pub mod modular_polynomial;
+pub mod montgomery;
+pub mod mov;
+pub mod number_theory;
+pub mod pairing;
 pub mod polynomial;
+pub mod random;
 pub mod rational_point;
+pub mod ring;
+pub mod sign;
 pub mod solution_set;
+pub mod square_root;
 
+use crate::classification::isomorphism_classes;
 use crate::complexification::Complex;
+use crate::ecm::Ecm;
+use crate::elliptic_curve::EllipticCurve;
 use crate::identities::{Identity, Zero};
+use crate::isogeny_graph::IsogenyGraph;
 use crate::modint::ModInt;
+use crate::number_theory::{is_prime, next_prime_3_mod_4, random_prime};
 use crate::polynomial::Polynomial;
+use crate::random::Rng;
 use crate::rational_point::RationalPoint;
 use crate::solution_set::SolutionSet;
 
@@ -34,6 +68,36 @@ fn main() {
         println!("注：p = {} のとき、x^2 = -1 となる x が F_p に存在するため、F_p[x] / (x^2 + 1) は体にならず、このプログラムでは F_p^2 を扱うことはできません。", P)
     }
 
+    if std::env::args().nth(1).as_deref() == Some("prime") {
+        let bits: u32 = std::env::args()
+            .nth(2)
+            .and_then(|bits| bits.trim().parse().ok())
+            .unwrap_or(32);
+        print_primes(bits);
+        return;
+    }
+    if std::env::args().nth(1).as_deref() == Some("factor") {
+        match std::env::args().nth(2).and_then(|n| n.trim().parse().ok()) {
+            Some(n) => print_factor(n),
+            None => {
+                println!("cargo run factor 91 のように、u64 に収まる整数を与えてください。")
+            }
+        }
+        return;
+    }
+    if std::env::args().nth(1).as_deref() == Some("classify") {
+        print_isomorphism_classes();
+        return;
+    }
+    if std::env::args().nth(1).as_deref() == Some("graph") {
+        let l: u64 = std::env::args()
+            .nth(2)
+            .and_then(|l| l.trim().parse().ok())
+            .unwrap_or(2);
+        print_isogeny_graph(l);
+        return;
+    }
+
     println!("係数aを入力");
 
     // 係数a
@@ -69,11 +133,12 @@ fn main() {
 
     let set: SolutionSet<(Complex<ModInt<P>>, Complex<ModInt<P>>)> = solve_equation(&f, &g);
 
-    if ModInt::<P>::new(16)
-        * (ModInt::<P>::new(4) * ModInt::<P>::new(a).modpow(3)
-            + ModInt::<P>::new(27) * ModInt::<P>::new(b).modpow(2))
-        == ModInt::<P>::zero()
-    {
+    let curve: EllipticCurve<Complex<ModInt<P>>> = EllipticCurve::new(
+        Complex::<ModInt<P>>::new(ModInt::<P>::new(a), ModInt::<P>::zero()),
+        Complex::<ModInt<P>>::new(ModInt::<P>::new(b), ModInt::<P>::zero()),
+    );
+
+    if curve.is_singular() {
         println!("注：Δ = -16(4a^3 + 27b^2) = 0 なので方程式 {} = {} が定義する曲線は楕円曲線にはなりません。", g.print_f_of_y(),
         f.print_f_of_x(),);
     }
@@ -87,7 +152,13 @@ fn main() {
     println!("解の個数は");
     println!("{}個", set.size());
     println!("です。");
-    println!("");
+    if let Some(j) = curve.j_invariant() {
+        println!("j不変量は j = {} です。", j);
+        if curve.is_supersingular() == Some(true) {
+            println!("この楕円曲線は超特異です。");
+        }
+    }
+    println!();
     println!("有理点の和 P + Q を計算します。");
 
     println!("P(p + qi, r + si) のpの入力");
@@ -173,26 +244,43 @@ fn main() {
     println!("P + Q = {}", point_r);
 }
 
-/// 素数判定
-fn is_prime(n: u64) -> bool {
-    if n == 2 {
-        return true;
+/// pとして使える素数を表示する。
+fn print_primes(bits: u32) {
+    let seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let mut rng = Rng::new(seed);
+    match random_prime(bits, (3, 4), &mut rng) {
+        Some(p) => println!("{}ビットの4で割って3余る素数の例：{}", bits, p),
+        None => println!("{}ビットの4で割って3余る素数はありません。", bits),
     }
-    if n % 2 == 0 {
-        return false;
+    println!(
+        "{}以上で4で割って3余る最小の素数（3を除く）：{}",
+        P,
+        next_prime_3_mod_4(P)
+    );
+}
+
+/// nが素数でなければ、楕円曲線法で約数を1つ探して表示する。
+fn print_factor(n: u64) {
+    if n < 2 {
+        println!("{}は素数でも合成数でもありません。", n);
+        return;
     }
-    if n == 0 || n == 1 {
-        return false;
+    if is_prime(n) {
+        println!("{}は素数です。", n);
+        return;
     }
-    for i in 0..n {
-        if n != 3 + 2 * i && n % (3 + 2 * i) == 0 {
-            return false;
-        }
-        if (3 + 2 * i) * (3 + 2 * i) >= n {
-            break;
-        }
+    match Ecm::default().find_factor(n, &mut Rng::new(n)) {
+        Some(d) => println!(
+            "楕円曲線法で約数が見つかりました：{} = {} × {}",
+            n,
+            d,
+            n / d
+        ),
+        None => println!("{}の約数は見つかりませんでした。", n),
     }
-    true
 }
 
 /// 方程式の解を全探索
@@ -224,6 +312,40 @@ fn solve_equation(
     SolutionSet::new(s)
 }
 
+/// F_p^2 上の楕円曲線を同型類ごとに表示
+fn print_isomorphism_classes() {
+    let classes = isomorphism_classes::<Complex<ModInt<P>>>();
+    println!(
+        "F_p^2 (p = {}) 上の楕円曲線の同型類は{}個あります。",
+        P,
+        classes.len()
+    );
+    for class in &classes {
+        println!("{}", class);
+    }
+}
+
+/// 超特異ℓ-同種写像グラフを表示
+fn print_isogeny_graph(l: u64) {
+    match IsogenyGraph::<Complex<ModInt<P>>>::supersingular(l) {
+        None => println!(
+            "ℓ = {} は p + 1 または p - 1 を割り切る素数ではないので、グラフを作れません。",
+            l
+        ),
+        Some(graph) => {
+            println!("F_p^2 (p = {}) 上の超特異{}-同種写像グラフ", P, l);
+            println!("{}", graph.to_adjacency_list());
+            println!("頂点の個数: {}", graph.vertex_count());
+            println!("連結: {}", graph.is_connected());
+            if let Some(diameter) = graph.diameter() {
+                println!("直径: {}", diameter);
+            }
+            println!("スペクトルギャップ: {}", graph.spectral_gap());
+            println!("{}", graph.to_dot());
+        }
+    }
+}
+
 fn print_solutions(ss: &SolutionSet<(Complex<ModInt<P>>, Complex<ModInt<P>>)>) {
     let mut s: String = String::new();
     if ss.size() == 0 {
@@ -247,9 +369,44 @@ fn print_solutions(ss: &SolutionSet<(Complex<ModInt<P>>, Complex<ModInt<P>>)>) {
 
 #[cfg(test)]
 mod tests {
-    use crate::complexification::Complex;
-    use crate::identities::Identity;
+    use crate::arithmetic::{is_square, power};
+    use crate::big_modint::{BigModInt, BigModulus, LIMBS};
+    use crate::characteristic::Characteristic;
+    use crate::classification::{isomorphism_classes, EllipticCurves};
+    use crate::complexification::{Complex, ParseComplexError};
+    use crate::coordinates::{
+        batch_to_affine, ChudnovskyPoint, Coordinates, JacobianPoint, ProjectivePoint,
+    };
+    use crate::crypto::{CryptoError, Domain, Signature};
+    use crate::ecm::Ecm;
+    use crate::elements::{Elements, Order};
+    use crate::elliptic_curve::{supersingular_j_invariants, EllipticCurve};
+    use crate::encoding::ByteEncoding;
+    use crate::extension_field::ExtensionField;
+    use crate::field::Field;
+    use crate::finite_field::FiniteField;
+    use crate::identities::{Identity, Zero};
+    use crate::integer::{FromInteger, ToInteger};
+    use crate::inverse::{batch_inverse, Inverse};
+    use crate::isogeny::Isogeny;
+    use crate::isogeny_graph::IsogenyGraph;
     use crate::modint::ModInt;
+    use crate::modular_polynomial::ModularPolynomial;
+    use crate::montgomery::MontgomeryModInt;
+    use crate::mov::MovError;
+    use crate::number_theory::{
+        factorize, is_prime, is_probable_prime, next_prime_3_mod_4, random_big_prime, random_prime,
+        MILLER_RABIN_ROUNDS,
+    };
+    use crate::pairing::PairingError;
+    use crate::polynomial::Polynomial;
+    use crate::random::{Random, Rng};
+    use crate::rational_point::RationalPoint;
+    use crate::ring::Ring;
+    use crate::square_root::SquareRoot;
+    use num::BigUint;
+    use std::collections::{HashMap, HashSet};
+    use std::hash::Hash;
 
     const P: u64 = 7;
 
@@ -260,6 +417,27 @@ mod tests {
         assert_eq!(x * x, y);
     }
 
+    #[test]
+    fn polynomial_mul_test() {
+        use crate::polynomial::Polynomial;
+        let m = |v: &[u64]| -> Polynomial<ModInt<P>> {
+            Polynomial::new(&v.iter().map(|&c| ModInt::<P>::new(c)).collect::<Vec<_>>())
+        };
+        // (1 + 2x + 3x^2)(4 + 5x + 6x^2 + x^3) = 4 + 13x + 28x^2 + 28x^3 + 20x^4 + 3x^5
+        let f = m(&[1, 2, 3]);
+        let g = m(&[4, 5, 6, 1]);
+        let h = f.clone() * g.clone();
+        assert_eq!(h.deg(), 5);
+        assert_eq!(h, m(&[4, 13, 28, 28, 20, 3]));
+        // 次数の積が次数の和より小さい場合も、全ての係数が残る
+        assert_eq!(m(&[1, 1]) * m(&[1, 1]), m(&[1, 2, 1]));
+        assert_eq!(m(&[0, 1]) * m(&[2, 0, 0, 1]), m(&[0, 2, 0, 0, 1]));
+        assert_eq!(m(&[3]) * g.clone(), m(&[12, 15, 18, 3]));
+        let mut k = f;
+        k *= g;
+        assert_eq!(k, h);
+    }
+
     #[test]
     fn inv_test() {
         for r in 0..P {
@@ -273,4 +451,1298 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn j_invariant_and_isomorphism_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        let e = EllipticCurve::new(c(1, 0), c(1, 0));
+        // u = 1 + i で y^2 = x^3 + (a / u^4) x + (b / u^6) に移す
+        let u = c(1, 1);
+        let u2 = u * u;
+        let e2 = EllipticCurve::new(e.a() * u2.modpow(P * P - 3), e.b() * u2.modpow(P * P - 4));
+        assert_eq!(e.j_invariant(), e2.j_invariant());
+        let iso = e.isomorphism(&e2).unwrap();
+        for point in e.points() {
+            assert!(e2.contains(&EllipticCurve::transform_point(&point, iso)));
+        }
+        assert_eq!(
+            EllipticCurve::new(c(1, 0), c(0, 0)).j_invariant(),
+            Some(c(1728, 0))
+        );
+        assert_eq!(
+            EllipticCurve::new(c(0, 0), c(1, 0)).j_invariant(),
+            Some(Complex::zero())
+        );
+    }
+
+    #[test]
+    fn twist_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        let q = P * P;
+        // 一般の j では #E + #E' = 2q + 2
+        let e = EllipticCurve::new(c(1, 0), c(2, 1));
+        let counts = e.twist_point_counts();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].1 + counts[1].1, 2 * q + 2);
+        assert!(!e.is_isomorphic(&counts[1].0));
+        // j = 1728 は4次ツイスト、j = 0 は6次ツイストが4個、6個
+        assert_eq!(EllipticCurve::new(c(1, 0), c(0, 0)).twists().len(), 4);
+        let sextic = EllipticCurve::new(c(0, 0), c(1, 0)).twist_point_counts();
+        assert_eq!(sextic.len(), 6);
+        for (curve, n) in sextic {
+            assert_eq!(curve.points().len() as u64, n);
+        }
+    }
+
+    #[test]
+    fn isomorphism_classes_test() {
+        let q = P * P;
+        let classes = isomorphism_classes::<Complex<ModInt<P>>>();
+        // 非特異な (a, b) は q^2 - q 個
+        let total: usize = classes.iter().map(|class| class.curves().len()).sum();
+        assert_eq!(total as u64, q * q - q);
+        assert_eq!(
+            EllipticCurves::<Complex<ModInt<P>>>::new().count() as u64,
+            q * q - q
+        );
+        // j ≠ 0, 1728 の類が 2(q - 2) 個、j = 1728 が4個、j = 0 が6個
+        assert_eq!(classes.len() as u64, 2 * (q - 2) + 4 + 6);
+        for class in &classes {
+            let (n1, n2) = class.group_structure();
+            assert_eq!(n2 % n1, 0);
+            assert_eq!((q - 1) % n1, 0);
+            assert_eq!(class.representative().count_points(), class.point_count());
+        }
+    }
+
+    #[test]
+    fn supersingular_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        // Hasse不変量による判定とFrobeniusのトレースによる判定が一致する
+        for curve in EllipticCurves::<Complex<ModInt<P>>>::new().step_by(7) {
+            assert_eq!(
+                curve.is_supersingular(),
+                Some(curve.frobenius_trace() % P as i64 == 0)
+            );
+        }
+        // p = 7 ≡ 7 (mod 12) なので超特異なj不変量は j = 1728 の1個だけ
+        assert_eq!(
+            supersingular_j_invariants::<Complex<ModInt<P>>>(),
+            vec![c(1728, 0)]
+        );
+        assert_eq!(supersingular_j_invariants::<Complex<ModInt<11>>>().len(), 2);
+        // 多項式の冪 (x^3 + ax + b)^((p - 1)/2) を実際に計算したものと一致する
+        fn check<T: FiniteField + Elements + std::fmt::Debug>() {
+            let p = num::ToPrimitive::to_u64(&T::characteristic()).unwrap();
+            for curve in EllipticCurves::<T>::new().step_by(5).take(50) {
+                let mut g = Polynomial::new_constant(T::identity());
+                for _ in 0..(p - 1) / 2 {
+                    g *= curve.polynomial();
+                }
+                assert_eq!(
+                    curve.hasse_invariant(),
+                    Some(g.coefficient((p - 1) as usize))
+                );
+            }
+        }
+        check::<ModInt<P>>();
+        check::<ModInt<103>>();
+        check::<Complex<ModInt<P>>>();
+        // 上限に近い p ≡ 3 (mod 4) でも y^2 = x^3 + x はすぐに超特異と判定できる
+        let curve = EllipticCurve::new(ModInt::<1048571>::new(1), ModInt::<1048571>::new(0));
+        assert_eq!(curve.is_supersingular(), Some(true));
+        // 上限を超える標数では判定しない。2^61 - 1 でも多項式を作らずにすぐ返る
+        let curve = EllipticCurve::new(
+            ModInt::<2305843009213693951>::new(1),
+            ModInt::<2305843009213693951>::new(0),
+        );
+        assert_eq!(curve.hasse_invariant(), None);
+        assert_eq!(curve.is_supersingular(), None);
+        let curve = EllipticCurve::new(
+            BigModInt::<Secp256k1>::zero(),
+            BigModInt::<Secp256k1>::from_u64(7),
+        );
+        assert_eq!(curve.hasse_invariant(), None);
+        assert_eq!(curve.is_supersingular(), None);
+    }
+
+    #[test]
+    fn velu_test() {
+        for curve in EllipticCurves::<Complex<ModInt<P>>>::new()
+            .step_by(97)
+            .take(10)
+        {
+            let n = curve.count_points();
+            let points = curve.points();
+            for l in [2, 3, 5] {
+                if n % l != 0 {
+                    continue;
+                }
+                let generator = points
+                    .iter()
+                    .map(|p| (p, curve.point_order(p, n)))
+                    .find(|(_, m)| m % l == 0)
+                    .map(|(p, m)| curve.multiply(p, m / l))
+                    .unwrap();
+                let phi = Isogeny::from_kernel_generator(&curve, &generator).unwrap();
+                let codomain = phi.codomain();
+                assert_eq!(phi.degree(), l);
+                assert!(!codomain.is_singular());
+                assert_eq!(codomain.count_points(), n);
+                assert_eq!(
+                    Isogeny::from_kernel_polynomial(&curve, phi.kernel_polynomial()).unwrap(),
+                    phi
+                );
+                let images: Vec<_> = points.iter().map(|p| phi.evaluate(p)).collect();
+                assert!(images.iter().all(|q| codomain.contains(q)));
+                assert_eq!(
+                    images.iter().filter(|q| **q == RationalPoint::O).count() as u64,
+                    l
+                );
+                for i in (0..points.len()).step_by(5) {
+                    let j = (3 * i + 1) % points.len();
+                    assert_eq!(
+                        phi.evaluate(&curve.add(&points[i], &points[j])),
+                        codomain.add(&images[i], &images[j])
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn supersingular_isogeny_graph_test() {
+        // p = 11 では超特異なj不変量は 0, 1728 の2個
+        for l in [2, 3, 5] {
+            let graph = IsogenyGraph::<Complex<ModInt<11>>>::supersingular(l).unwrap();
+            assert_eq!(graph.vertex_count(), 2);
+            assert!(graph.edges().iter().all(|row| row.len() as u64 == l + 1));
+            assert!(graph.is_connected());
+            assert_eq!(graph.diameter(), Some(1));
+            let eigenvalues = graph.eigenvalues();
+            assert!((eigenvalues[0] - (l + 1) as f64).abs() < 1e-9);
+            assert!(eigenvalues[1].abs() <= 2.0 * (l as f64).sqrt() + 1e-9);
+        }
+        assert!(IsogenyGraph::<Complex<ModInt<11>>>::supersingular(7).is_none());
+        let graph = IsogenyGraph::<Complex<ModInt<P>>>::supersingular(2).unwrap();
+        assert_eq!(graph.to_adjacency_list(), "6: 6, 6, 6");
+        assert!(graph.to_dot().contains("\"6\" -> \"6\";"));
+    }
+
+    #[test]
+    fn modular_polynomial_test() {
+        // Φ_2(X, Y) = X^3 + Y^3 - X^2Y^2 + 1488(X^2Y + XY^2) - 162000(X^2 + Y^2)
+        //             + 40773375XY + 8748000000(X + Y) - 157464000000000
+        let phi2 = ModularPolynomial::new(2).unwrap();
+        let expected: [(usize, usize, i64); 10] = [
+            (3, 0, 1),
+            (2, 2, -1),
+            (2, 1, 1488),
+            (2, 0, -162000),
+            (1, 1, 40773375),
+            (1, 0, 8748000000),
+            (0, 0, -157464000000000),
+            (0, 3, 1),
+            (1, 2, 1488),
+            (0, 2, -162000),
+        ];
+        for (i, k, c) in expected {
+            assert_eq!(*phi2.coefficient(i, k), num::BigInt::from(c));
+        }
+        assert_eq!(*phi2.coefficient(3, 3), num::BigInt::from(0));
+        assert!(ModularPolynomial::new(4).is_none());
+        // 256ビットの素体でも係数を法で割った余りとして扱える
+        let j = BigModInt::<Secp256k1>::identity();
+        let phi2_at_1 = phi2.evaluate_x(j);
+        let modulus = num::BigInt::from(Secp256k1::modulus());
+        for k in 0..=3 {
+            let c: num::BigInt = (0..=3).map(|i| phi2.coefficient(i, k)).sum();
+            let c = num::Integer::mod_floor(&c, &modulus).to_biguint().unwrap();
+            assert_eq!(phi2_at_1.coefficient(k), BigModInt::new(&c));
+        }
+        // Φ_ℓ(j, Y) の根は超特異同種写像グラフの隣接頂点と重複込みで一致する
+        for l in [2, 3, 5] {
+            let phi = ModularPolynomial::new(l).unwrap();
+            for i in 0..=(l as usize + 1) {
+                for k in 0..=(l as usize + 1) {
+                    assert_eq!(phi.coefficient(i, k), phi.coefficient(k, i));
+                }
+            }
+            let graph = IsogenyGraph::<Complex<ModInt<11>>>::supersingular(l).unwrap();
+            for (v, row) in graph.edges().iter().enumerate() {
+                let mut expected: Vec<_> = row.iter().map(|&w| graph.vertices()[w]).collect();
+                let mut neighbours = phi.neighbours(graph.vertices()[v]);
+                expected.sort_by_key(|z| z.to_string());
+                neighbours.sort_by_key(|z| z.to_string());
+                assert_eq!(neighbours, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn weil_pairing_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        // y^2 = x^3 + x は F_49 上で (p + 1)^2 = 64 個の有理点をもち、E[8] が全て有理的
+        let curve = EllipticCurve::new(c(1, 0), c(0, 0));
+        let n = 8;
+        let points = curve.points();
+        assert_eq!(points.len(), 64);
+        let p = points
+            .iter()
+            .find(|p| curve.order_of_point(p) == n)
+            .unwrap();
+        let (q, e) = points
+            .iter()
+            .filter_map(|q| Some((q, curve.weil_pairing(p, q, n).ok()?)))
+            .find(|(_, e)| e.modpow(n / 2) != Complex::identity())
+            .unwrap();
+        // 非退化：e_n(P, Q) は1の原始n乗根
+        assert_eq!(e.modpow(n), Complex::identity());
+        // 交代性
+        assert_eq!(curve.weil_pairing(p, p, n), Ok(Complex::identity()));
+        assert_eq!(
+            e * curve.weil_pairing(q, p, n).unwrap(),
+            Complex::identity()
+        );
+        // 双線形性
+        for &r in points.iter().step_by(7) {
+            let left = curve.weil_pairing(&curve.add(p, &r), q, n).unwrap();
+            let right = e * curve.weil_pairing(&r, q, n).unwrap();
+            assert_eq!(left, right);
+        }
+        for (a, b) in [(2, 3), (3, 5), (5, 7)] {
+            let ap = curve.multiply(p, a);
+            let bq = curve.multiply(q, b);
+            assert_eq!(curve.weil_pairing(&ap, &bq, n).unwrap(), e.modpow(a * b));
+        }
+        // n等分点でない点
+        assert_eq!(curve.weil_pairing(p, q, 3), Err(PairingError::NotTorsion));
+        let outside = RationalPoint::new(c(1, 0), c(1, 0));
+        assert_eq!(
+            curve.weil_pairing(&outside, q, n),
+            Err(PairingError::NotOnCurve)
+        );
+    }
+
+    #[test]
+    fn tate_pairing_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        assert_eq!(crate::number_theory::embedding_degree(49, 8), Some(1));
+        assert_eq!(crate::number_theory::embedding_degree(7, 8), Some(2));
+        assert_eq!(crate::number_theory::embedding_degree(49, 7), None);
+        let curve = EllipticCurve::new(c(1, 0), c(0, 0));
+        let r = 8;
+        let points = curve.points();
+        let p = points
+            .iter()
+            .find(|p| curve.order_of_point(p) == r)
+            .unwrap();
+        // 自分自身とのペアリングも退化しうるが、Qを動かせば1の原始r乗根が現れる
+        let (q, t) = points
+            .iter()
+            .map(|q| (q, curve.tate_pairing(p, q, r).unwrap()))
+            .find(|(_, t)| t.modpow(r / 2) != Complex::identity())
+            .unwrap();
+        assert_eq!(t.modpow(r), Complex::identity());
+        for (a, b) in [(2, 3), (3, 5), (5, 1)] {
+            let ap = curve.multiply(p, a);
+            let bq = curve.multiply(q, b);
+            assert_eq!(curve.tate_pairing(&ap, &bq, r).unwrap(), t.modpow(a * b));
+        }
+        // rE の元を足しても値は変わらない
+        let rs = curve.multiply(&points[5], r);
+        assert_eq!(curve.tate_pairing(p, &curve.add(q, &rs), r).unwrap(), t);
+        // p と互いに素でない r 、埋め込み次数が1でない r
+        assert_eq!(curve.tate_pairing(p, q, 3), Err(PairingError::NotTorsion));
+        let e = EllipticCurve::new(c(1, 0), c(1, 0));
+        let n = e.count_points();
+        assert_eq!(n, 55);
+        let r = crate::number_theory::factorize(n).last().unwrap().0;
+        assert_eq!(r, 11);
+        let g = e
+            .points()
+            .into_iter()
+            .find(|g| e.order_of_point(g) % r == 0)
+            .unwrap();
+        let g = e.multiply(&g, e.order_of_point(&g) / r);
+        assert_eq!(e.embedding_degree(r), Some(5));
+        assert_eq!(
+            e.tate_pairing(&g, &g, r),
+            Err(PairingError::EmbeddingDegree(5))
+        );
+        // E(F_7) が位数3の巡回群 {O, P, 2P} だと、零点や極を避ける補助の点がとれない
+        let e = EllipticCurves::<ModInt<P>>::new()
+            .find(|e| e.count_points() == 3)
+            .unwrap();
+        assert_eq!(e.embedding_degree(3), Some(1));
+        let g = e.points()[1];
+        assert_eq!(
+            e.tate_pairing(&g, &e.multiply(&g, 2), 3),
+            Err(PairingError::NoAuxiliaryPoint)
+        );
+    }
+
+    #[test]
+    fn discrete_log_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        // 位数が素数 61 の曲線
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        assert_eq!(curve.count_points(), 61);
+        let p = curve.points()[1];
+        for k in [0, 1, 2, 17, 42, 60] {
+            let q = curve.multiply(&p, k);
+            assert_eq!(curve.baby_step_giant_step(&p, &q, 61), Some(k));
+            assert_eq!(curve.pollard_rho(&p, &q, 61), Some(k));
+            assert_eq!(curve.pohlig_hellman(&p, &q, 61), Some(k));
+        }
+        // 位数が合成数の点
+        let curve = EllipticCurve::new(c(1, 0), c(2, 1));
+        let n = curve.count_points();
+        let p = curve
+            .points()
+            .into_iter()
+            .max_by_key(|p| curve.order_of_point(p))
+            .unwrap();
+        let order = curve.order_of_point(&p);
+        for k in [3, order / 2 + 1, order - 1] {
+            let q = curve.multiply(&p, k);
+            assert_eq!(curve.discrete_log(&p, &q), Some(k));
+            assert_eq!(curve.baby_step_giant_step(&p, &q, order), Some(k));
+        }
+        // Pの生成する部分群に入っていない点
+        let outside = curve
+            .points()
+            .into_iter()
+            .find(|q| curve.order_of_point(q) != 1 && order % curve.order_of_point(q) != 0);
+        if let Some(q) = outside {
+            assert_eq!(curve.pohlig_hellman(&p, &q, order), None);
+        }
+        assert!(n % order == 0);
+    }
+
+    #[test]
+    fn extension_field_test() {
+        fn check<T: FiniteField + Order + std::fmt::Debug + 'static, const K: usize>() {
+            type E<T, const K: usize> = ExtensionField<T, K>;
+            let c = E::<T, K>::modulus().unwrap();
+            let generator =
+                E::<T, K>::from_polynomial(&Polynomial::new(&[T::zero(), T::identity()])).unwrap();
+            assert_eq!(power(generator, K as u64), E::embed(c));
+            let mut rng = Rng::new(0);
+            for _ in 0..20 {
+                let x = E::<T, K>::random(&mut rng);
+                if x == E::zero() {
+                    continue;
+                }
+                assert_eq!(x * x.inverse().unwrap(), E::identity());
+                assert_eq!(power(x, E::<T, K>::order() - 1), E::identity());
+                let y = (x * x).square_root().unwrap();
+                assert!(y == x || y == -x);
+                assert_eq!(E::from_polynomial(&x.to_polynomial()), Some(x));
+            }
+        }
+        check::<ModInt<P>, 2>();
+        check::<ModInt<P>, 3>();
+        check::<ModInt<13>, 4>();
+        check::<Complex<ModInt<P>>, 6>();
+        // 7 ≡ 3 mod 4 なので x^4 - c は既約にならず、5 ∤ 7 - 1 なので x^5 - c も既約にならない
+        assert_eq!(ExtensionField::<ModInt<P>, 4>::modulus(), None);
+        assert_eq!(ExtensionField::<ModInt<P>, 5>::modulus(), None);
+    }
+
+    #[test]
+    fn mov_attack_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        // 超特異曲線 y^2 = x^3 + x では E[8] が有理的なのでWeilペアリングで帰着できる
+        let curve = EllipticCurve::new(c(1, 0), c(0, 0));
+        let n = 8;
+        let p = curve
+            .points()
+            .into_iter()
+            .find(|p| curve.order_of_point(p) == n)
+            .unwrap();
+        for k in [1, 3, 5, 6] {
+            let q = curve.multiply(&p, k);
+            let reduction = curve.mov_attack(&p, &q, n).unwrap();
+            assert_eq!(reduction.embedding_degree(), 1);
+            assert_eq!(reduction.log(), k);
+            assert_eq!(
+                reduction.alpha().coefficient(0).modpow(k),
+                reduction.beta().coefficient(0)
+            );
+        }
+        // E(F_q) が巡回群で 3 | q - 1 のときはTateペアリングで帰着する
+        let (curve, n) = EllipticCurves::<Complex<ModInt<P>>>::new()
+            .map(|e| (e, e.group_structure()))
+            .find(|(_, (n1, n2))| *n1 == 1 && n2 % 3 == 0)
+            .map(|(e, (_, n2))| (e, n2))
+            .unwrap();
+        let g = curve
+            .points()
+            .into_iter()
+            .find(|g| curve.order_of_point(g) == n)
+            .unwrap();
+        let p = curve.multiply(&g, n / 3);
+        let q = curve.multiply(&p, 2);
+        assert_eq!(curve.mov_attack(&p, &q, 3).unwrap().log(), 2);
+        assert_eq!(curve.mov_attack(&p, &g, 3), Err(MovError::NoSolution));
+        // 曲線上にない点は最初に弾く。q と n が互いに素でない場合と、埋め込み次数が大きすぎる場合
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        let p = curve.points()[1];
+        let outside = RationalPoint::Point(c(0, 0), c(0, 0));
+        assert!(!curve.contains(&outside));
+        assert_eq!(
+            curve.mov_attack(&p, &outside, 61),
+            Err(MovError::Pairing(PairingError::NotOnCurve))
+        );
+        assert_eq!(
+            curve.mov_attack(&RationalPoint::O, &RationalPoint::O, P),
+            Err(MovError::Pairing(PairingError::NotCoprime))
+        );
+        assert_eq!(
+            curve.mov_attack(&p, &p, 61),
+            Err(MovError::Pairing(PairingError::EmbeddingDegree(30)))
+        );
+        // 埋め込み次数がDの場合は F_q^D に持ち上げて帰着する。ペアリングの値は F_q に入らない
+        fn check_extension<T, const D: usize>(curve: EllipticCurve<T>, n: u64)
+        where
+            T: FiniteField + Hash + Order + Elements + std::fmt::Debug + 'static,
+        {
+            assert_eq!(curve.embedding_degree(n), Some(D as u64));
+            let g = curve
+                .points()
+                .into_iter()
+                .find(|g| curve.order_of_point(g).is_multiple_of(n))
+                .unwrap();
+            let p = curve.multiply(&g, curve.order_of_point(&g) / n);
+            let lift = |f: &Polynomial<T>| ExtensionField::<T, D>::from_polynomial(f).unwrap();
+            let lifted = EllipticCurve::new(
+                ExtensionField::embed(curve.a()),
+                ExtensionField::embed(curve.b()),
+            );
+            for k in [1, 2, n / 2, n - 1] {
+                let q = curve.multiply(&p, k);
+                let reduction = curve.mov_attack(&p, &q, n).unwrap();
+                assert_eq!(reduction.embedding_degree(), D as u64);
+                assert_eq!(reduction.log(), k);
+                assert_eq!(power(lift(&reduction.alpha()), k), lift(&reduction.beta()));
+                assert_ne!(reduction.alpha().deg(), 0);
+                let r = match reduction.auxiliary_point() {
+                    RationalPoint::Point(x, y) => RationalPoint::Point(lift(&x), lift(&y)),
+                    RationalPoint::O => RationalPoint::O,
+                };
+                assert!(lifted.contains(&r));
+            }
+        }
+        // 超特異曲線 y^2 = x^3 + x over F_p (p ≡ 3 mod 4) は #E = p + 1 で埋め込み次数が2
+        check_extension::<_, 2>(
+            EllipticCurve::new(ModInt::<P>::new(1), ModInt::<P>::new(0)),
+            8,
+        );
+        check_extension::<_, 2>(
+            EllipticCurve::new(ModInt::<43>::new(1), ModInt::<43>::new(0)),
+            11,
+        );
+        // F_p^2 上のトレース0の超特異曲線は #E = p^2 + 1 = 50 で、n = 25 の埋め込み次数は2
+        let curve = EllipticCurves::<Complex<ModInt<P>>>::new()
+            .find(|e| e.count_points() == P * P + 1)
+            .unwrap();
+        check_extension::<_, 2>(curve, 25);
+        // F_p^2 (p = 11) 上のトレースpの超特異曲線は #E = p^2 - p + 1 = 111 = 3 * 37 で、
+        // 37 | p^6 - 1 なので n = 37 の埋め込み次数は3
+        let curve = EllipticCurves::<Complex<ModInt<11>>>::new()
+            .find(|e| e.count_points() == 111)
+            .unwrap();
+        check_extension::<_, 3>(curve, 37);
+        // 埋め込み次数が4だが、q ≡ 3 mod 4 なので x^4 - c が既約にならない場合
+        let curve = EllipticCurves::<ModInt<P>>::new()
+            .find(|e| e.count_points() == 5)
+            .unwrap();
+        let p = curve.points()[1];
+        assert_eq!(curve.embedding_degree(5), Some(4));
+        assert_eq!(curve.mov_attack(&p, &p, 5), Err(MovError::Extension));
+    }
+
+    #[test]
+    fn ecdh_ecdsa_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        // y^2 = x^3 + (5 + i) の有理点は61個で、61は素数
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        let g = curve.points()[1];
+        let domain = Domain::new(curve, g, 61).unwrap();
+        assert_eq!(Domain::new(curve, g, 60), Err(CryptoError::InvalidDomain));
+        assert_eq!(domain.private_key(61), Err(CryptoError::InvalidPrivateKey));
+
+        let alice = domain.private_key(17).unwrap();
+        let bob = domain.private_key(42).unwrap();
+        let alice_public = domain.public_key(&alice);
+        let bob_public = domain.public_key(&bob);
+        assert_eq!(
+            domain.shared_secret(&alice, &bob_public),
+            domain.shared_secret(&bob, &alice_public)
+        );
+
+        let bytes = alice_public.to_bytes();
+        assert_eq!(domain.public_key_from_bytes(&bytes), Ok(alice_public));
+        let mut broken = bytes.clone();
+        broken[4] ^= 1;
+        assert!(domain.public_key_from_bytes(&broken).is_err());
+        assert_eq!(
+            domain.public_key_from_bytes(&alice_public.to_compressed_bytes()),
+            Ok(alice_public)
+        );
+        assert_eq!(domain.private_key_from_bytes(&alice.to_bytes()), Ok(alice));
+
+        let signature = domain.sign(&alice, b"hello");
+        assert_eq!(signature, domain.sign(&alice, b"hello"));
+        assert_eq!(
+            Signature::from_bytes(&signature.to_bytes()),
+            Some(signature)
+        );
+        assert!(domain.verify(&alice_public, b"hello", &signature));
+        assert!(!domain.verify(&bob_public, b"hello", &signature));
+        // 同じ r で検証を通る s は、s と n - s の2つだけ
+        let valid: Vec<u64> = (1u64..61)
+            .filter(|s| {
+                let bytes = [signature.r().to_be_bytes(), s.to_be_bytes()].concat();
+                domain.verify(
+                    &alice_public,
+                    b"hello",
+                    &Signature::from_bytes(&bytes).unwrap(),
+                )
+            })
+            .collect();
+        let mut expected = vec![signature.s(), 61 - signature.s()];
+        expected.sort();
+        assert_eq!(valid, expected);
+    }
+
+    #[test]
+    fn square_root_test() {
+        for x in ModInt::<P>::elements() {
+            assert_eq!(x.square_root().is_some(), is_square(x));
+        }
+        for x in Complex::<ModInt<P>>::elements() {
+            match x.square_root() {
+                Some(r) => assert_eq!(r * r, x),
+                None => assert!(!is_square(x)),
+            }
+        }
+        // 41 - 1 = 5 * 2^3 なのでTonelli–Shanks法のループを通る
+        for x in ModInt::<41>::elements() {
+            match x.square_root() {
+                Some(r) => assert_eq!(r * r, x),
+                None => assert!(!is_square(x)),
+            }
+        }
+    }
+
+    #[test]
+    fn elgamal_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        let domain = Domain::new(curve, curve.points()[1], 61).unwrap();
+        let private_key = domain.private_key(23).unwrap();
+        let public_key = domain.public_key(&private_key);
+        for m in 0..12 {
+            let point = curve.encode_integer(m, 4).unwrap();
+            assert!(curve.contains(&point));
+            let ciphertext = domain.encrypt(&public_key, &point, 5 + m).unwrap();
+            let decrypted = domain.decrypt(&private_key, &ciphertext);
+            assert_eq!(curve.decode_integer(&decrypted, 4), Some(m));
+        }
+        // x座標の候補 52 が位数 49 を超える
+        assert_eq!(curve.encode_integer(13, 4), None);
+        let point = curve.encode_integer(3, 4).unwrap();
+        assert_eq!(
+            domain.encrypt(&public_key, &point, 61),
+            Err(CryptoError::InvalidNonce)
+        );
+
+        // バイト列は1点に1バイトずつ入る
+        type F = Complex<ModInt<1019>>;
+        let c = |r: u64, i: u64| F::new(ModInt::new(r), ModInt::new(i));
+        let curve = EllipticCurve::new(c(3, 1), c(2, 7));
+        let points = curve.encode_bytes(b"hello", 16).unwrap();
+        assert_eq!(points.len(), 6);
+        assert!(points.iter().all(|p| curve.contains(p)));
+        assert_eq!(curve.decode_bytes(&points, 16), Some(b"hello".to_vec()));
+        assert_eq!(curve.decode_bytes(&points[..5], 16), None);
+    }
+
+    #[test]
+    fn hash_to_curve_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        let curve = EllipticCurve::new(c(3, 1), c(2, 5));
+        assert!(!curve.is_singular());
+        for message in [&b""[..], b"abc", b"hello"] {
+            let p = curve
+                .hash_to_curve_try_and_increment(message, b"test")
+                .unwrap();
+            let q = curve.hash_to_curve_swu(message, b"test").unwrap();
+            assert!(curve.contains(&p) && curve.contains(&q));
+            assert_eq!(curve.hash_to_curve_swu(message, b"test"), Some(q));
+        }
+        let z = curve.swu_parameter().unwrap();
+        assert!(z.square_root().is_none());
+        // SWU写像の像は点全体のうちかなりの割合を占める
+        let images: HashSet<_> = Complex::<ModInt<P>>::elements()
+            .into_iter()
+            .map(|u| curve.map_to_curve_swu(u).unwrap())
+            .collect();
+        assert!(images.iter().all(|p| curve.contains(p)));
+        assert!(4 * images.len() as u64 >= curve.count_points());
+        // a = 0 の曲線では使えない
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        assert_eq!(curve.hash_to_curve_swu(b"abc", b"test"), None);
+    }
+
+    #[test]
+    fn point_encoding_test() {
+        let c =
+            |r: u64, i: u64| Complex::<ModInt<P>>::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        assert_eq!(c(3, 5).to_bytes(), vec![3, 5]);
+        assert_eq!(Complex::<ModInt<P>>::from_bytes(&[3, 7]), None);
+        assert_eq!(ModInt::<65537>::new(65536).to_bytes(), vec![1, 0, 0]);
+
+        let curve = EllipticCurve::new(c(3, 1), c(2, 5));
+        for point in curve.points() {
+            let uncompressed = point.to_uncompressed_bytes();
+            let compressed = point.to_compressed_bytes();
+            assert_eq!(curve.point_from_bytes(&uncompressed), Some(point));
+            assert_eq!(curve.point_from_bytes(&compressed), Some(point));
+            if point != RationalPoint::O {
+                assert_eq!(uncompressed.len(), 5);
+                assert_eq!(compressed.len(), 3);
+            }
+        }
+        // 曲線上にない点や、知らない先頭バイトは受け付けない
+        let p = curve.points()[1];
+        let mut bytes = p.to_uncompressed_bytes();
+        bytes[3] = (bytes[3] + 1) % 7;
+        assert_eq!(curve.point_from_bytes(&bytes), None);
+        bytes[0] = 0x05;
+        assert_eq!(curve.point_from_bytes(&bytes), None);
+        assert_eq!(curve.point_from_bytes(&[]), None);
+    }
+
+    #[test]
+    fn random_test() {
+        type F = Complex<ModInt<P>>;
+        let mut rng = Rng::new(2024);
+        let first: Vec<F> = (0..10).map(|_| F::random(&mut rng)).collect();
+        let mut rng = Rng::new(2024);
+        let again: Vec<F> = (0..10).map(|_| F::random(&mut rng)).collect();
+        assert_eq!(first, again);
+
+        // 各元・各点がおよそ同じ回数ずつ出る
+        let mut counts: HashMap<F, u64> = HashMap::new();
+        for _ in 0..49 * 200 {
+            *counts.entry(F::random(&mut rng)).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 49);
+        assert!(counts.values().all(|&n| (120..280).contains(&n)));
+
+        let curve = EllipticCurve::<F>::random(&mut rng);
+        assert!(!curve.is_singular());
+        let n = curve.count_points();
+        let mut counts: HashMap<RationalPoint<F>, u64> = HashMap::new();
+        for _ in 0..n * 200 {
+            let point = curve.random_point(&mut rng);
+            assert!(curve.contains(&point));
+            *counts.entry(point).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len() as u64, n);
+        assert!(counts.values().all(|&n| (120..280).contains(&n)));
+    }
+
+    #[test]
+    fn large_complex_test() {
+        // 2^32 より大きい素数 p ≡ 3 mod 4 。p^2 は u64 に収まらない
+        const Q: u64 = 4294967311;
+        type F = Complex<ModInt<Q>>;
+        assert_eq!(F::cardinality(), BigUint::from(Q) * BigUint::from(Q));
+        let mut rng = Rng::new(43);
+        let curve = EllipticCurve::<F>::random(&mut rng);
+        let points: HashSet<RationalPoint<F>> =
+            (0..20).map(|_| curve.random_point(&mut rng)).collect();
+        assert!(points.iter().all(|p| curve.contains(p)));
+        assert_eq!(points.len(), 20);
+        let x = F::new(ModInt::new(Q - 1), ModInt::new(0));
+        assert_eq!(x.to_integer(), Q - 1);
+        assert_eq!(F::from_integer(Q - 1), Some(x));
+    }
+
+    #[test]
+    #[should_panic(expected = "F_p^2 の位数が u64 に収まりません。")]
+    fn large_complex_order_test() {
+        Complex::<ModInt<4294967311>>::order();
+    }
+
+    #[test]
+    #[should_panic(expected = "F_p^2 の元が u64 に収まりません。")]
+    fn large_complex_to_integer_test() {
+        Complex::<ModInt<4294967311>>::new(ModInt::new(0), ModInt::new(4294967310)).to_integer();
+    }
+
+    #[test]
+    fn coordinates_test() {
+        type F = Complex<ModInt<P>>;
+        let mut rng = Rng::new(41);
+        for _ in 0..5 {
+            let curve = EllipticCurve::<F>::random(&mut rng);
+            for _ in 0..5 {
+                let p = curve.random_point(&mut rng);
+                let q = curve.random_point(&mut rng);
+                let sum = curve.add(&p, &q);
+                let (pp, qp) = (
+                    ProjectivePoint::from_affine(&p),
+                    ProjectivePoint::from_affine(&q),
+                );
+                let (pj, qj) = (
+                    JacobianPoint::from_affine(&p),
+                    JacobianPoint::from_affine(&q),
+                );
+                let (pc, qc) = (
+                    ChudnovskyPoint::from_affine(&p),
+                    ChudnovskyPoint::from_affine(&q),
+                );
+                assert_eq!(pp.add(&qp, curve.a()).to_affine(), sum);
+                assert_eq!(pj.add(&qj, curve.a()).to_affine(), sum);
+                assert_eq!(pc.add(&qc, curve.a()).to_affine(), sum);
+                assert_eq!(pj.double(curve.a()).to_affine(), curve.add(&p, &p));
+                for n in [0, 1, 2, 7, 30, 61, 1000] {
+                    let expected = curve.multiply(&p, n);
+                    assert_eq!(curve.multiply_in::<ProjectivePoint<F>>(&p, n), expected);
+                    assert_eq!(curve.multiply_in::<JacobianPoint<F>>(&p, n), expected);
+                    assert_eq!(curve.multiply_in::<ChudnovskyPoint<F>>(&p, n), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_test() {
+        type F = Complex<ModInt<P>>;
+        for x in F::elements() {
+            match x.inverse() {
+                Some(y) => assert_eq!(x * y, F::identity()),
+                None => assert_eq!(x, F::zero()),
+            }
+        }
+        // 素数でない法でも、互いに素な元は逆元を持つ
+        assert_eq!(ModInt::<12>::new(5).inverse(), Some(ModInt::new(5)));
+        assert_eq!(ModInt::<12>::new(4).inverse(), None);
+
+        let values = F::elements();
+        let inverses = batch_inverse(&values);
+        for (x, y) in values.iter().zip(inverses) {
+            assert_eq!(y, x.inverse());
+        }
+
+        let mut rng = Rng::new(42);
+        let curve = EllipticCurve::<F>::random(&mut rng);
+        let p = curve.random_point(&mut rng);
+        let mut points = vec![JacobianPoint::from_affine(&p)];
+        for _ in 0..20 {
+            let last = *points.last().unwrap();
+            points.push(last.add(&points[0], curve.a()));
+        }
+        let expected: Vec<_> = points.iter().map(|q| q.to_affine()).collect();
+        assert_eq!(batch_to_affine(&points), expected);
+    }
+
+    #[test]
+    fn modint_overflow_test() {
+        // 2^64 未満で最大の素数
+        const M: u64 = 18446744073709551557;
+        type F = ModInt<M>;
+        let minus_one = F::new(M - 1);
+        assert_eq!(F::new(u64::MAX), F::new(58));
+        assert_eq!(minus_one + minus_one, F::new(M - 2));
+        assert_eq!(minus_one * minus_one, F::identity());
+        assert_eq!(F::zero() - F::identity(), minus_one);
+        assert_eq!(-minus_one, F::identity());
+        assert_eq!(-F::zero(), F::zero());
+        assert_eq!(minus_one.inverse(), Some(minus_one));
+        assert_eq!(F::new(2).modpow(M - 1), F::identity());
+        let mut x = F::new(M - 3);
+        x += F::new(M - 5);
+        x -= F::new(M - 7);
+        x *= F::new(M - 2);
+        assert_eq!(x, F::new(2));
+        let r = F::new(4).square_root().unwrap();
+        assert!(r == F::new(2) || r == F::new(M - 2));
+
+        // 2^32 を少し超える素数で、u128 で計算した結果と比べる
+        const N: u64 = 4294967311;
+        let mut rng = Rng::new(43);
+        for _ in 0..1000 {
+            let a = rng.below(N);
+            let b = rng.below(N);
+            let c = ModInt::<N>::new(a) * ModInt::<N>::new(b);
+            assert_eq!(c.to_int() as u128, a as u128 * b as u128 % N as u128);
+            let d = ModInt::<N>::new(a) + ModInt::<N>::new(b);
+            assert_eq!(d.to_int(), (a + b) % N);
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Secp256k1;
+
+    impl BigModulus for Secp256k1 {
+        const MODULUS: [u64; LIMBS] = [
+            0xfffffffefffffc2f,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+        ];
+    }
+
+    /// 2^255 - 19 。4で割って1余るのでTonelli–Shanks法を使う。
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    struct Curve25519;
+
+    impl BigModulus for Curve25519 {
+        const MODULUS: [u64; LIMBS] = [
+            0xffffffffffffffed,
+            0xffffffffffffffff,
+            0xffffffffffffffff,
+            0x7fffffffffffffff,
+        ];
+    }
+
+    #[test]
+    fn big_modint_test() {
+        type F = BigModInt<Secp256k1>;
+        let hex = |s: &str| F::new(&BigUint::parse_bytes(s.as_bytes(), 16).unwrap());
+        // secp256k1 の基点Gとその2倍
+        let curve = EllipticCurve::new(F::zero(), F::from_u64(7));
+        let g = RationalPoint::Point(
+            hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        );
+        let g2 = RationalPoint::Point(
+            hex("C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5"),
+            hex("1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A"),
+        );
+        assert!(curve.contains(&g));
+        assert_eq!(curve.add(&g, &g), g2);
+        assert_eq!(curve.multiply(&g, 3), curve.add(&g2, &g));
+        assert_eq!(F::characteristic(), Secp256k1::modulus());
+        assert_eq!(F::byte_length(), 32);
+
+        let mut rng = Rng::new(44);
+        for _ in 0..5 {
+            let x = BigModInt::<Curve25519>::random(&mut rng);
+            let r = (x * x).square_root().unwrap();
+            assert!(r == x || r == -x);
+        }
+
+        // F_p^2 = F_p[i] 上の曲線
+        type G = Complex<F>;
+        for _ in 0..5 {
+            let x = G::random(&mut rng);
+            assert_eq!(x * x.inverse().unwrap(), G::identity());
+            if let Some(r) = x.square_root() {
+                assert_eq!(r * r, x);
+            }
+            assert_eq!(G::from_bytes(&x.to_bytes()), Some(x));
+        }
+        let curve = EllipticCurve::<G>::random(&mut rng);
+        let p = (0..)
+            .find_map(|_| {
+                let x = G::random(&mut rng);
+                let y = (x * x * x + curve.a() * x + curve.b()).square_root()?;
+                Some(RationalPoint::Point(x, y))
+            })
+            .unwrap();
+        assert!(curve.contains(&p));
+        let p2 = curve.add(&p, &p);
+        assert_eq!(curve.add(&p2, &p), curve.add(&p, &p2));
+        assert_eq!(
+            curve.multiply(&p, 1000),
+            curve.multiply_in::<JacobianPoint<G>>(&p, 1000)
+        );
+
+        let f = Polynomial::new(&[G::identity(), G::identity()]);
+        let g = Polynomial::new(&[-G::identity(), G::identity()]);
+        assert_eq!(
+            f * g,
+            Polynomial::new(&[-G::identity(), G::zero(), G::identity()])
+        );
+    }
+
+    #[test]
+    fn montgomery_test() {
+        const M: u64 = 18446744073709551557;
+        type F = MontgomeryModInt<M>;
+        let mut rng = Rng::new(45);
+        for _ in 0..1000 {
+            let (a, b) = (rng.below(M), rng.below(M));
+            let (x, y) = (F::new(a), F::new(b));
+            let (u, v) = (ModInt::<M>::new(a), ModInt::<M>::new(b));
+            assert_eq!(x.to_int(), a);
+            assert_eq!((x * y).to_int(), (u * v).to_int());
+            assert_eq!((x + y).to_int(), (u + v).to_int());
+            assert_eq!((x - y).to_int(), (u - v).to_int());
+            assert_eq!((-x).to_int(), (-u).to_int());
+        }
+        assert_eq!(F::new(3).modpow(M - 1), F::identity());
+        assert_eq!(F::new(M - 1).inverse(), Some(F::new(M - 1)));
+
+        // 曲線の計算も```ModInt```と同じ結果になる
+        type G = Complex<MontgomeryModInt<P>>;
+        let c = |r: u64, i: u64| G::new(MontgomeryModInt::new(r), MontgomeryModInt::new(i));
+        let curve = EllipticCurve::new(c(0, 0), c(5, 1));
+        assert_eq!(curve.count_points(), 61);
+    }
+
+    /// ```cargo test --release -- --ignored --nocapture```で実行する。
+    /// 時間は実行環境に左右されるので比べた結果は表示するだけにして、計算結果が一致することだけを確かめる。
+    /// 計測のぶれを抑えるため、それぞれ数回測って最小の時間を表示する。
+    #[test]
+    #[ignore]
+    fn montgomery_benchmark() {
+        use std::time::{Duration, Instant};
+        const M: u64 = 18446744073709551557;
+        const N: u64 = 10_000_000;
+        const RUNS: usize = 5;
+        let mut modint = Duration::MAX;
+        let mut montgomery = Duration::MAX;
+        for _ in 0..RUNS {
+            let start = Instant::now();
+            let mut x = ModInt::<M>::new(3);
+            for _ in 0..N {
+                x = x * x + ModInt::new(1);
+            }
+            modint = modint.min(start.elapsed());
+            let start = Instant::now();
+            let mut y = MontgomeryModInt::<M>::new(3);
+            for _ in 0..N {
+                y = y * y + MontgomeryModInt::new(1);
+            }
+            montgomery = montgomery.min(start.elapsed());
+            assert_eq!(x.to_int(), y.to_int());
+        }
+        println!(
+            "ModInt: {:?}, MontgomeryModInt: {:?} ({:.2}倍)",
+            modint,
+            montgomery,
+            modint.as_secs_f64() / montgomery.as_secs_f64()
+        );
+    }
+
+    #[test]
+    fn primality_test() {
+        // 小さい数は試し割りと比べる
+        for n in 0..2000 {
+            assert_eq!(is_prime(n), n >= 2 && factorize(n) == vec![(n, 1)]);
+        }
+        assert_eq!(
+            factorize(u64::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65537, 1),
+                (6700417, 1)
+            ]
+        );
+        // Carmichael数や、小さい底に対する強擬素数
+        for n in [561, 3215031751, 2152302898747, 3825123056546413051] {
+            assert!(!is_prime(n));
+        }
+        for n in [2305843009213693951, 18446744073709551557, 4294967311] {
+            assert!(is_prime(n));
+        }
+        assert!(!is_prime(u64::MAX));
+        const Q: u64 = next_prime_3_mod_4(100);
+        assert_eq!(Q, 103);
+        assert_eq!(next_prime_3_mod_4(0), 7);
+        assert_eq!(next_prime_3_mod_4(8), 11);
+        assert_eq!(next_prime_3_mod_4(20), 23);
+        assert_eq!(next_prime_3_mod_4(24), 31);
+
+        let mut rng = Rng::new(46);
+        for bits in [2, 3, 10, 32, 63, 64] {
+            let p = random_prime(bits, (1, 2), &mut rng).unwrap();
+            assert!(is_prime(p));
+            assert_eq!(64 - p.leading_zeros(), bits);
+        }
+        for _ in 0..20 {
+            let p = random_prime(20, (3, 4), &mut rng).unwrap();
+            assert!(is_prime(p) && p % 4 == 3 && p >> 19 == 1);
+        }
+        assert_eq!(random_prime(2, (1, 4), &mut rng), None);
+        assert_eq!(random_prime(10, (2, 4), &mut rng), None);
+        assert_eq!(random_prime(65, (3, 4), &mut rng), None);
+        // 候補を毎回引き直すので、間隔の大きい素数の直後の素数に偏らない。
+        // 15ビットの素数は1612個あり、各素数は平均12.4回ほど選ばれる
+        let mut counts: HashMap<u64, u32> = HashMap::new();
+        for _ in 0..20000 {
+            *counts
+                .entry(random_prime(15, (1, 2), &mut rng).unwrap())
+                .or_insert(0) += 1;
+        }
+        assert!(counts.len() > 1550);
+        assert!(counts.values().all(|&n| n < 40));
+        // 候補が少ない場合は全て調べる
+        let primes: HashSet<u64> = (0..200)
+            .map(|_| random_prime(7, (1, 2), &mut rng).unwrap())
+            .collect();
+        assert_eq!(primes.len(), 13);
+        // 8 以上 15 以下で 7 で割って1余る奇数は 15 だけ
+        assert_eq!(random_prime(4, (1, 7), &mut rng), None);
+
+        // 多倍長整数
+        let m127 = (BigUint::from(1u32) << 127) - 1u32;
+        let m61 = BigUint::from(2305843009213693951u64);
+        assert!(is_probable_prime(&m127, MILLER_RABIN_ROUNDS, &mut rng));
+        assert!(!is_probable_prime(
+            &(&m127 * &m61),
+            MILLER_RABIN_ROUNDS,
+            &mut rng
+        ));
+        assert!(!is_probable_prime(
+            &(&m127 * &m127),
+            MILLER_RABIN_ROUNDS,
+            &mut rng
+        ));
+        let p = random_big_prime(128, (3, 4), &mut rng).unwrap();
+        assert_eq!(p.bits(), 128);
+        assert_eq!(&p % 4u32, BigUint::from(3u32));
+        assert!(is_probable_prime(&p, MILLER_RABIN_ROUNDS, &mut rng));
+    }
+
+    #[test]
+    fn finite_field_test() {
+        type F = Complex<ModInt<P>>;
+        assert_eq!(ModInt::<P>::degree(), 1);
+        assert_eq!(F::degree(), 2);
+        assert_eq!(F::cardinality(), BigUint::from(49u32));
+        for x in F::elements() {
+            assert_eq!(x.frobenius(), x.modpow(P));
+            assert_eq!(x.frobenius().frobenius(), x);
+        }
+        for x in ModInt::<P>::elements() {
+            assert_eq!(x.frobenius(), x);
+        }
+        assert_eq!(
+            BigModInt::<Secp256k1>::cardinality(),
+            BigModInt::<Secp256k1>::characteristic()
+        );
+        assert_eq!(
+            Complex::<BigModInt<Secp256k1>>::cardinality(),
+            Secp256k1::modulus().pow(2)
+        );
+
+        // 境界を```Ring```や```Field```にまとめた汎用関数が、どの体の型でも使える
+        fn sum_of_squares<T: Ring>(xs: &[T]) -> T {
+            xs.iter().fold(T::zero(), |acc, &x| acc + x * x)
+        }
+        fn solve_linear<T: Field>(a: T, b: T) -> Option<T> {
+            Some(-b * a.inverse()?)
+        }
+        let x = Complex::new(ModInt::<P>::new(3), ModInt::<P>::new(2));
+        assert_eq!(sum_of_squares(&[x, x]), x * x + x * x);
+        assert_eq!(
+            solve_linear(x, F::identity()).map(|y| x * y),
+            Some(-F::identity())
+        );
+        assert_eq!(solve_linear(F::zero(), F::identity()), None);
+        let m = MontgomeryModInt::<P>::new(3);
+        assert_eq!(solve_linear(m, m), Some(-MontgomeryModInt::<P>::identity()));
+    }
+
+    #[test]
+    fn num_traits_test() {
+        use num::traits::{Inv, Pow};
+        use num::{Num, One};
+        type F = Complex<ModInt<P>>;
+        let c = |r: u64, i: u64| F::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+
+        assert!(num::Zero::is_zero(&<ModInt<P> as num::Zero>::zero()));
+        assert!(!num::Zero::is_zero(&ModInt::<P>::one()));
+        assert!(num::Zero::is_zero(&<F as num::Zero>::zero()));
+        assert_eq!(F::one(), F::identity());
+        for x in F::elements() {
+            assert_eq!(x.pow(10), x.modpow(10));
+            // ```num```の汎用的な関数
+            assert_eq!(num::pow(x, 10), x.modpow(10));
+            if x != F::zero() {
+                assert_eq!(x.inv(), x.inverse().unwrap());
+                assert_eq!(c(3, 2) / x * x, c(3, 2));
+                assert_eq!(c(3, 2) % x, F::zero());
+            }
+        }
+        for x in ModInt::<P>::elements().into_iter().skip(1) {
+            assert_eq!(x.inv() * x, ModInt::one());
+            assert_eq!(ModInt::<P>::new(5) / x * x, ModInt::new(5));
+        }
+
+        // ModInt の from_str_radix
+        assert_eq!(ModInt::<P>::from_str_radix("12", 10), Ok(ModInt::new(5)));
+        assert_eq!(ModInt::<P>::from_str_radix("-1", 10), Ok(ModInt::new(6)));
+        assert_eq!(ModInt::<P>::from_str_radix("ff", 16), Ok(ModInt::new(255)));
+        assert_eq!(
+            ModInt::<P>::from_str_radix("123456789012345678901234567890", 10),
+            Ok(ModInt::new(
+                (123456789012345678901234567890u128 % P as u128) as u64
+            ))
+        );
+        assert!(ModInt::<P>::from_str_radix("", 10).is_err());
+        assert!(ModInt::<P>::from_str_radix("1x", 10).is_err());
+
+        // Complex の from_str_radix は Display の出力を読める
+        for x in F::elements() {
+            assert_eq!(F::from_str_radix(&x.to_string(), 10), Ok(x));
+        }
+        assert_eq!(F::from_str_radix("3+2i", 10), Ok(c(3, 2)));
+        assert_eq!(F::from_str_radix("3 - 2i", 10), Ok(c(3, 5)));
+        assert_eq!(F::from_str_radix("-i", 10), Ok(c(0, 6)));
+        assert_eq!(F::from_str_radix("(1 + i)", 10), Ok(c(1, 1)));
+        assert_eq!(F::from_str_radix("11", 2), Ok(c(3, 0)));
+        assert_eq!(
+            F::from_str_radix("(1 + 2i", 10),
+            Err(ParseComplexError::Format)
+        );
+        assert_eq!(F::from_str_radix("1", 36), Err(ParseComplexError::Format));
+        assert!(matches!(
+            F::from_str_radix("1 + xi", 10),
+            Err(ParseComplexError::Part(_))
+        ));
+
+        // Num を要求する汎用的なコード
+        fn parse_sum<T: Num>(items: &[&str]) -> Option<T> {
+            items.iter().try_fold(T::zero(), |acc, item| {
+                Some(acc + T::from_str_radix(item, 10).ok()?)
+            })
+        }
+        assert_eq!(parse_sum::<F>(&["1 + i", "2i", "6"]), Some(c(0, 3)));
+        assert_eq!(parse_sum::<ModInt<P>>(&["3", "4", "x"]), None);
+    }
+
+    #[test]
+    fn division_test() {
+        use num::CheckedDiv;
+        type F = Complex<ModInt<P>>;
+        let c = |r: u64, i: u64| F::new(ModInt::<P>::new(r), ModInt::<P>::new(i));
+        for x in F::elements() {
+            for y in F::elements() {
+                if y == F::zero() {
+                    assert_eq!(x.checked_div(&y), None);
+                    continue;
+                }
+                assert_eq!(x / y * y, x);
+                assert_eq!(x.checked_div(&y), Some(x / y));
+                let mut z = x;
+                z /= y;
+                assert_eq!(z, x / y);
+            }
+        }
+        assert_eq!(c(1, 0) / c(0, 1), c(0, 6));
+
+        // 合成数を法とする場合も、拡張ユークリッドの互除法で逆元が求まる元では割れる
+        type N = ModInt<15>;
+        assert_eq!(N::new(7) / N::new(2), N::new(11));
+        assert_eq!(N::new(2).inverse(), Some(N::new(8)));
+        assert_eq!(N::new(7).checked_div(&N::new(3)), None);
+        assert_eq!(N::new(7).checked_div(&N::new(10)), None);
+        assert_eq!(N::new(7).checked_div(&N::new(4)), Some(N::new(13)));
+        for x in 0..15 {
+            let expected = (1..15).find(|&y| x * y % 15 == 1).map(N::new);
+            assert_eq!(N::new(x).inverse(), expected);
+        }
+
+        let m = MontgomeryModInt::<P>::new(3);
+        assert_eq!(m / MontgomeryModInt::new(5) * MontgomeryModInt::new(5), m);
+        assert_eq!(m.checked_div(&MontgomeryModInt::new(0)), None);
+        let mut b = BigModInt::<Secp256k1>::from_u64(10);
+        b /= BigModInt::from_u64(4);
+        assert_eq!(b * BigModInt::from_u64(2), BigModInt::from_u64(5));
+        assert_eq!(b.checked_div(&BigModInt::from_u64(0)), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn division_by_zero_test() {
+        let _ = ModInt::<P>::new(3) / ModInt::<P>::new(0);
+    }
+
+    #[test]
+    fn ecm_test() {
+        // 素数を法とするときは通常の足し算と一致する
+        let curve = EllipticCurve::new(ModInt::<P>::new(3), ModInt::<P>::new(1));
+        for p in curve.points() {
+            for q in curve.points() {
+                assert_eq!(curve.try_add(&p, &q), Ok(curve.add(&p, &q)));
+            }
+            assert_eq!(curve.try_multiply(&p, 5), Ok(curve.multiply(&p, 5)));
+        }
+
+        // 合成数を法とするときは、逆元がとれない分母から約数が見つかる
+        const M: u64 = 91;
+        let curve = EllipticCurve::new(ModInt::<M>::new(1), ModInt::<M>::new(1));
+        let p = RationalPoint::Point(ModInt::<M>::new(0), ModInt::<M>::new(1));
+        let q = RationalPoint::Point(ModInt::<M>::new(7), ModInt::<M>::new(1));
+        assert_eq!(curve.try_add(&p, &q), Err(7));
+
+        let ecm = Ecm::new(50, 2000, 100);
+        assert_eq!(
+            (ecm.stage1_bound(), ecm.stage2_bound(), ecm.curves()),
+            (50, 2000, 100)
+        );
+        assert_eq!(Ecm::new(100, 10, 1).stage2_bound(), 100);
+        let mut rng = Rng::new(50);
+        let mut check = |ecm: &Ecm, n: u64| {
+            let d = ecm.find_factor(n, &mut rng).unwrap();
+            assert!(d != 1 && d != n && n.is_multiple_of(d), "{} {}", n, d);
+        };
+        check(&ecm, 91);
+        check(&ecm, 25);
+        check(&ecm, 1024);
+        check(&Ecm::default(), 1000003 * 1000033);
+        check(&Ecm::default(), 2147483647 * 2147483629);
+        // 2^64 に近い合成数も扱える
+        check(&Ecm::default(), 4294967291 * 4294967279);
+        assert_eq!(ecm.find_factor(18446744073709551557, &mut rng), None);
+        assert_eq!(ecm.find_factor(1, &mut rng), None);
+        // 曲線が1つもなければ見つからない
+        assert_eq!(Ecm::new(50, 2000, 0).find_factor(91, &mut rng), None);
+    }
+
+    /// ```+```演算子の係数 a を与えるためのテスト用の実装。y^2 = x^3 + x over F_11 。
+    impl crate::rational_point::A for ModInt<11> {
+        fn a() -> Self {
+            ModInt::new(1)
+        }
+    }
+
+    #[test]
+    fn rational_point_add_operator_test() {
+        let curve = EllipticCurve::new(ModInt::<11>::new(1), ModInt::<11>::new(0));
+        let points = curve.points();
+        // y = 0 の点 (0, 0) は位数2なので、2倍すると無限遠点になる
+        let t = RationalPoint::Point(ModInt::<11>::new(0), ModInt::<11>::new(0));
+        assert!(curve.contains(&t));
+        assert_eq!(t + t, RationalPoint::O);
+        for &p in &points {
+            for &q in &points {
+                assert_eq!(p + q, curve.add(&p, &q));
+            }
+        }
+    }
 }
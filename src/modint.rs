@@ -1,10 +1,22 @@
 use crate::characteristic::Characteristic;
+use crate::elements::{Elements, Order};
+use crate::encoding::ByteEncoding;
+use crate::finite_field::FiniteField;
 use crate::identities::{Identity, Zero};
+use crate::integer::{FromInteger, ToInteger};
 use crate::inverse::Inverse;
+use crate::number_theory::{mod_inverse, mul_mod};
+use crate::random::{Random, Rng};
+use crate::sign::Sign;
+use crate::square_root::SquareRoot;
+use num::bigint::ParseBigIntError;
+use num::traits::{CheckedDiv, Inv, Pow};
+use num::{BigUint, Num, ToPrimitive};
 use std::fmt;
-use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
 
 /// ```MOD```で割った余り。Z / MOD Z の元。
+/// 掛け算は u128 で計算するので、```MOD```は```u64```の範囲全体で使える。
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct ModInt<const MOD: u64> {
     representative: u64,
@@ -33,10 +45,10 @@ impl<const MOD: u64> ModInt<MOD> {
                 break;
             }
             if m % 2 == 1 {
-                res = (res * a) % MOD;
+                res = mul_mod(res, a, MOD);
             }
-            a = (a * a) % MOD;
-            m = m / 2;
+            a = mul_mod(a, a, MOD);
+            m /= 2;
         }
         ModInt {
             representative: res,
@@ -44,6 +56,24 @@ impl<const MOD: u64> ModInt<MOD> {
     }
 }
 
+/// a + b mod MOD 。a, b < MOD のとき、MOD が 2^63 を超えていてもオーバーフローしない。
+pub(crate) fn add_mod<const MOD: u64>(a: u64, b: u64) -> u64 {
+    if a >= MOD - b {
+        a - (MOD - b)
+    } else {
+        a + b
+    }
+}
+
+/// a - b mod MOD 。a, b < MOD とする。
+pub(crate) fn sub_mod<const MOD: u64>(a: u64, b: u64) -> u64 {
+    if a >= b {
+        a - b
+    } else {
+        a + (MOD - b)
+    }
+}
+
 /// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
 impl<const MOD: u64> fmt::Display for ModInt<MOD> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -55,7 +85,7 @@ impl<const MOD: u64> Add for ModInt<MOD> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative + rhs.representative) % MOD,
+            representative: add_mod::<MOD>(self.representative, rhs.representative),
         }
     }
 }
@@ -63,7 +93,7 @@ impl<const MOD: u64> Add for ModInt<MOD> {
 impl<const MOD: u64> AddAssign for ModInt<MOD> {
     fn add_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative + other.representative) % MOD,
+            representative: add_mod::<MOD>(self.representative, other.representative),
         };
     }
 }
@@ -72,7 +102,7 @@ impl<const MOD: u64> Sub for ModInt<MOD> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative + MOD - rhs.representative) % MOD,
+            representative: sub_mod::<MOD>(self.representative, rhs.representative),
         }
     }
 }
@@ -80,7 +110,7 @@ impl<const MOD: u64> Sub for ModInt<MOD> {
 impl<const MOD: u64> SubAssign for ModInt<MOD> {
     fn sub_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative + MOD - other.representative) % MOD,
+            representative: sub_mod::<MOD>(self.representative, other.representative),
         };
     }
 }
@@ -89,7 +119,7 @@ impl<const MOD: u64> Mul for ModInt<MOD> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
         ModInt {
-            representative: (self.representative * rhs.representative) % MOD,
+            representative: mul_mod(self.representative, rhs.representative, MOD),
         }
     }
 }
@@ -97,16 +127,50 @@ impl<const MOD: u64> Mul for ModInt<MOD> {
 impl<const MOD: u64> MulAssign for ModInt<MOD> {
     fn mul_assign(&mut self, other: Self) {
         *self = Self {
-            representative: (self.representative * other.representative) % MOD,
+            representative: mul_mod(self.representative, other.representative, MOD),
         };
     }
 }
 
+/// 逆元を掛ける。逆元が存在しない元（0を含む）で割るとpanicする。
+impl<const MOD: u64> Div for ModInt<MOD> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs
+            .inverse()
+            .expect("逆元が存在しない元で割ることはできません。")
+    }
+}
+
+impl<const MOD: u64> DivAssign for ModInt<MOD> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+/// 割り算。逆元が存在しない元（0を含む）で割る場合は```None```を返す。
+impl<const MOD: u64> CheckedDiv for ModInt<MOD> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        Some(*self * v.inverse()?)
+    }
+}
+
+/// 割り切れるので余りは常に0。```Div```と同じく、逆元が存在しない元で割るとpanicする。
+impl<const MOD: u64> Rem for ModInt<MOD> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self - self / rhs * rhs
+    }
+}
+
 /// unary negation の実装
 impl<const MOD: u64> Neg for ModInt<MOD> {
     type Output = Self;
     fn neg(self) -> Self {
-        ModInt::<MOD>::new(MOD - self.representative)
+        ModInt {
+            representative: sub_mod::<MOD>(0, self.representative),
+        }
     }
 }
 
@@ -122,20 +186,182 @@ impl<const MOD: u64> Identity for ModInt<MOD> {
     }
 }
 
+/// 拡張ユークリッドの互除法による逆元。```MOD```が素数でなくても、互いに素な元なら逆元を返す。
 impl<const MOD: u64> Inverse for ModInt<MOD> {
     fn inverse(self) -> Option<ModInt<MOD>> {
-        let n = self.to_int();
-        if num::Integer::gcd(&n, &MOD) != 1 {
+        mod_inverse(self.representative, MOD).map(ModInt::new)
+    }
+}
+
+impl<const MOD: u64> Characteristic for ModInt<MOD> {
+    fn characteristic() -> BigUint {
+        BigUint::from(MOD)
+    }
+}
+
+impl<const MOD: u64> Order for ModInt<MOD> {
+    fn order() -> u64 {
+        MOD
+    }
+}
+
+impl<const MOD: u64> Elements for ModInt<MOD> {
+    fn elements() -> Vec<Self> {
+        (0..MOD).map(ModInt::new).collect()
+    }
+}
+
+impl<const MOD: u64> ToInteger for ModInt<MOD> {
+    fn to_integer(&self) -> u64 {
+        self.representative
+    }
+}
+
+impl<const MOD: u64> FromInteger for ModInt<MOD> {
+    fn from_integer(n: u64) -> Option<Self> {
+        if n < MOD {
+            Some(ModInt::new(n))
+        } else {
             None
+        }
+    }
+}
+
+/// Tonelli–Shanks法による平方根。```MOD```は奇素数であることを想定している。
+impl<const MOD: u64> SquareRoot for ModInt<MOD> {
+    fn square_root(self) -> Option<Self> {
+        if self.representative == 0 || MOD == 2 {
+            return Some(self);
+        }
+        if self.modpow((MOD - 1) / 2).to_int() != 1 {
+            return None;
+        }
+        // MOD - 1 = q 2^s (qは奇数)
+        let mut q = MOD - 1;
+        let mut s = 0;
+        while q.is_multiple_of(2) {
+            q /= 2;
+            s += 1;
+        }
+        let z = (2..MOD)
+            .map(ModInt::<MOD>::new)
+            .find(|z| z.modpow((MOD - 1) / 2).to_int() == MOD - 1)?;
+        let mut m = s;
+        let mut c = z.modpow(q);
+        let mut t = self.modpow(q);
+        let mut r = self.modpow(q.div_ceil(2));
+        while t.to_int() != 1 {
+            // t^(2^i) = 1 となる最小の i
+            let mut i = 0;
+            let mut t2 = t;
+            while t2.to_int() != 1 {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            if i == m {
+                return None;
+            }
+            let b = c.modpow(1 << (m - i - 1));
+            m = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+        if r * r == self {
+            Some(r)
         } else {
-            let ret = self.modpow(MOD - 2);
-            Some(ret)
+            None
         }
     }
 }
 
-impl<const MOD: u64> Characteristic for ModInt<MOD> {
-    fn characteristic() -> u64 {
-        MOD
+/// 代表元が奇数なら```true```。
+impl<const MOD: u64> Sign for ModInt<MOD> {
+    fn sign(&self) -> bool {
+        self.representative % 2 == 1
+    }
+}
+
+impl<const MOD: u64> ByteEncoding for ModInt<MOD> {
+    fn byte_length() -> usize {
+        let bits = 64 - (MOD - 1).leading_zeros() as usize;
+        bits.div_ceil(8).max(1)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.representative.to_be_bytes()[(8 - Self::byte_length())..].to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::byte_length() {
+            return None;
+        }
+        let n = bytes.iter().fold(0, |n, &byte| (n << 8) | byte as u64);
+        Self::from_integer(n)
+    }
+}
+
+/// 素体 F_p 。Frobenius写像は恒等写像になる。
+impl<const MOD: u64> FiniteField for ModInt<MOD> {
+    fn degree() -> u32 {
+        1
+    }
+
+    fn frobenius(self) -> Self {
+        self
+    }
+}
+
+impl<const MOD: u64> Random for ModInt<MOD> {
+    fn random(rng: &mut Rng) -> Self {
+        ModInt::new(rng.below(MOD))
+    }
+}
+
+// 以下は```num```クレートのトレイト。```num```の汎用的な関数をそのまま使えるようにする。
+
+impl<const MOD: u64> num::Zero for ModInt<MOD> {
+    fn zero() -> Self {
+        ModInt::new(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.representative == 0
+    }
+}
+
+impl<const MOD: u64> num::One for ModInt<MOD> {
+    fn one() -> Self {
+        ModInt::new(1)
+    }
+}
+
+impl<const MOD: u64> Pow<u64> for ModInt<MOD> {
+    type Output = Self;
+    fn pow(self, rhs: u64) -> Self::Output {
+        self.modpow(rhs)
+    }
+}
+
+/// 逆元が存在しない元ではpanicする。panicさせたくない場合は```Inverse```を使う。
+impl<const MOD: u64> Inv for ModInt<MOD> {
+    type Output = Self;
+    fn inv(self) -> Self::Output {
+        self.inverse().expect("逆元が存在しません。")
+    }
+}
+
+/// ```from_str_radix```は整数として読んでから```MOD```で割った余りにする。先頭の```-```は符号として扱う。
+impl<const MOD: u64> Num for ModInt<MOD> {
+    type FromStrRadixErr = ParseBigIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        let (negative, digits) = match str.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, str),
+        };
+        let n = BigUint::from_str_radix(digits, radix)?;
+        let x = ModInt::new((n % MOD).to_u64().unwrap());
+        Ok(if negative { -x } else { x })
     }
 }
@@ -0,0 +1,255 @@
+use crate::arithmetic::from_biguint;
+use crate::characteristic::Characteristic;
+use crate::elements::Elements;
+use crate::finite_field::FiniteField;
+use crate::number_theory::is_prime;
+use crate::polynomial::Polynomial;
+use crate::ring::Ring;
+use num::{BigInt, Integer};
+
+/// 古典的なモジュラー多項式 Φ_ℓ(X, Y) 。ℓは素数。
+///
+/// 係数は整数で、j(E) と j(E') が Φ_ℓ(j(E), j(E')) = 0 を満たすことと
+/// E と E' がℓ次の同種写像で結ばれていることが同値になる。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModularPolynomial {
+    l: u64,
+    coefficients: Vec<Vec<BigInt>>,
+}
+
+/// q展開（Laurent級数）。coefficients\[i\] が q^(low + i) の係数。
+#[derive(Clone, Debug)]
+struct Series {
+    low: i64,
+    coefficients: Vec<BigInt>,
+}
+
+impl Series {
+    /// q^high の項までで打ち切った積
+    fn mul(&self, rhs: &Self, high: i64) -> Self {
+        let low = self.low + rhs.low;
+        let len = (high - low + 1).max(0) as usize;
+        let mut coefficients = vec![BigInt::from(0); len];
+        for (i, c) in self.coefficients.iter().enumerate() {
+            for (j, d) in rhs.coefficients.iter().enumerate() {
+                if i + j >= len {
+                    break;
+                }
+                coefficients[i + j] += c * d;
+            }
+        }
+        Series { low, coefficients }
+    }
+
+    /// q^e の係数
+    fn coefficient(&self, e: i64) -> BigInt {
+        if e < self.low || e - self.low >= self.coefficients.len() as i64 {
+            BigInt::from(0)
+        } else {
+            self.coefficients[(e - self.low) as usize].clone()
+        }
+    }
+
+    /// q^low から q^high までの係数を足し合わせた級数 c_1 self + c_2 rhs
+    fn combine(&self, c1: &BigInt, rhs: &Self, c2: &BigInt, high: i64) -> Self {
+        let low = self.low.min(rhs.low);
+        let coefficients = (low..=high)
+            .map(|e| c1 * self.coefficient(e) + c2 * rhs.coefficient(e))
+            .collect();
+        Series { low, coefficients }
+    }
+
+    /// 定数項が1の冪級数の逆元
+    fn inverse(&self, high: i64) -> Self {
+        let len = (high + 1) as usize;
+        let mut coefficients = vec![BigInt::from(0); len];
+        coefficients[0] = BigInt::from(1);
+        for n in 1..len {
+            let mut s = BigInt::from(0);
+            for k in 1..=n {
+                s += self.coefficient(k as i64) * &coefficients[n - k];
+            }
+            coefficients[n] = -s;
+        }
+        Series {
+            low: 0,
+            coefficients,
+        }
+    }
+}
+
+/// j(q) = E_4(q)^3 / Δ(q) = 1/q + 744 + 196884q + ... を q^high の項まで求める。
+fn j_series(high: i64) -> Series {
+    let len = (high + 2) as usize;
+    let mut e4 = vec![BigInt::from(0); len];
+    e4[0] = BigInt::from(1);
+    for (n, c) in e4.iter_mut().enumerate().skip(1) {
+        let sigma3: u64 = (1..=n as u64)
+            .filter(|d| (n as u64).is_multiple_of(*d))
+            .map(|d| d * d * d)
+            .sum();
+        *c = BigInt::from(240) * BigInt::from(sigma3);
+    }
+    let e4 = Series {
+        low: 0,
+        coefficients: e4,
+    };
+    let top = high + 1;
+    // Π(1 - q^n)
+    let mut eta = Series {
+        low: 0,
+        coefficients: vec![BigInt::from(1)],
+    };
+    for n in 1..=top {
+        let mut factor = vec![BigInt::from(0); n as usize + 1];
+        factor[0] = BigInt::from(1);
+        factor[n as usize] = BigInt::from(-1);
+        eta = eta.mul(
+            &Series {
+                low: 0,
+                coefficients: factor,
+            },
+            top,
+        );
+    }
+    let mut eta24 = Series {
+        low: 0,
+        coefficients: vec![BigInt::from(1)],
+    };
+    for _ in 0..24 {
+        eta24 = eta24.mul(&eta, top);
+    }
+    let j = e4.mul(&e4, top).mul(&e4, top).mul(&eta24.inverse(top), top);
+    Series {
+        low: -1,
+        coefficients: j.coefficients,
+    }
+}
+
+impl ModularPolynomial {
+    /// Φ_ℓ を j のq展開から計算する。ℓが素数でない場合は```None```を返す。
+    ///
+    /// Φ_ℓ(j(q), Y) の根は j(q^ℓ) と j(ζ^k q^(1/ℓ)) (k = 0, ..., ℓ - 1) なので、
+    /// 根の冪和をq展開で求め、Newtonの恒等式で基本対称式にし、それをjの多項式として書き直す。
+    pub fn new(l: u64) -> Option<Self> {
+        if !is_prime(l) {
+            return None;
+        }
+        let li = l as i64;
+        let n = (l + 1) as usize;
+        // qについての精度と、q^(1/ℓ) についての精度
+        let high = li * (li + 1) + 1;
+        let high_root = li * high + li + 1;
+
+        let j = j_series(high_root);
+        let mut j_powers = vec![Series {
+            low: 0,
+            coefficients: vec![BigInt::from(1)],
+        }];
+        for m in 1..=n {
+            j_powers.push(j_powers[m - 1].mul(&j, high_root));
+        }
+
+        // 根の冪和 s_m
+        let mut power_sums = vec![j_powers[0].clone()];
+        for jm in j_powers.iter().skip(1) {
+            let low = jm.low * li;
+            let coefficients = (low..=high)
+                .map(|e| {
+                    let mut c = BigInt::from(0);
+                    if e % li == 0 {
+                        c += jm.coefficient(e / li);
+                    }
+                    c + BigInt::from(l) * jm.coefficient(e * li)
+                })
+                .collect();
+            power_sums.push(Series { low, coefficients });
+        }
+
+        // Newtonの恒等式 m e_m = Σ (-1)^(i - 1) e_(m - i) s_i
+        let mut elementary = vec![j_powers[0].clone()];
+        for m in 1..=n {
+            let mut e = Series {
+                low: 0,
+                coefficients: Vec::new(),
+            };
+            for i in 1..=m {
+                let sign = BigInt::from(if i % 2 == 1 { 1 } else { -1 });
+                let term = elementary[m - i].mul(&power_sums[i], high);
+                e = e.combine(&BigInt::from(1), &term, &sign, high);
+            }
+            let coefficients = e
+                .coefficients
+                .iter()
+                .map(|c| c.div_floor(&BigInt::from(m)))
+                .collect();
+            elementary.push(Series {
+                low: e.low,
+                coefficients,
+            });
+        }
+
+        // e_m を j の多項式に書き直す。係数は (-1)^m をかけて Y^(ℓ + 1 - m) の係数にする。
+        let mut coefficients = vec![vec![BigInt::from(0); n + 1]; n + 1];
+        for (m, e) in elementary.iter().enumerate() {
+            let mut rest = e.clone();
+            for k in (0..=n).rev() {
+                let c = rest.coefficient(-(k as i64));
+                if c != BigInt::from(0) {
+                    rest = rest.combine(&BigInt::from(1), &j_powers[k], &-c.clone(), high);
+                }
+                coefficients[k][n - m] = if m % 2 == 0 { c } else { -c };
+            }
+        }
+        Some(Self { l, coefficients })
+    }
+
+    /// ℓ
+    pub fn l(&self) -> u64 {
+        self.l
+    }
+
+    /// X^i Y^k の係数
+    pub fn coefficient(&self, i: usize, k: usize) -> &BigInt {
+        &self.coefficients[i][k]
+    }
+}
+
+impl ModularPolynomial {
+    /// 整数の係数を型```T```の元にする。
+    fn reduce<T>(c: &BigInt) -> T
+    where
+        T: Ring + Characteristic,
+    {
+        let p = BigInt::from(T::characteristic());
+        from_biguint::<T>(c.mod_floor(&p).magnitude())
+    }
+
+    /// X に j を代入して、Yの多項式 Φ_ℓ(j, Y) にする。
+    pub fn evaluate_x<T>(&self, j: T) -> Polynomial<T>
+    where
+        T: Ring + Characteristic,
+    {
+        let n = self.coefficients.len();
+        let v: Vec<T> = (0..n)
+            .map(|k| {
+                let mut s = T::zero();
+                let mut j_pow = T::identity();
+                for i in 0..n {
+                    s += Self::reduce::<T>(&self.coefficients[i][k]) * j_pow;
+                    j_pow *= j;
+                }
+                s
+            })
+            .collect();
+        Polynomial::new(&v)
+    }
+
+    /// j とℓ次の同種写像で結ばれるj不変量を、Φ_ℓ(j, Y) の根として重複込みで求める。
+    pub fn neighbours<T>(&self, j: T) -> Vec<T>
+    where
+        T: FiniteField + Elements,
+    {
+        Polynomial::roots(&self.evaluate_x(j))
+    }
+}
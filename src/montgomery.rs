@@ -0,0 +1,277 @@
+use crate::characteristic::Characteristic;
+use crate::elements::{Elements, Order};
+use crate::encoding::ByteEncoding;
+use crate::finite_field::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::integer::{FromInteger, ToInteger};
+use crate::inverse::Inverse;
+use crate::modint::{add_mod, sub_mod, ModInt};
+use crate::random::{Random, Rng};
+use crate::sign::Sign;
+use crate::square_root::SquareRoot;
+use num::traits::CheckedDiv;
+use num::BigUint;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Montgomery表現で持つ Z / MOD Z の元。```MOD```は奇数である必要がある。
+///
+/// R = 2^64 として、元 x を xR mod MOD の形で持つ。掛け算は u128 の積を```%```で割る代わりに
+/// Montgomery reduction (REDC) で求めるので、割り算が要らず```ModInt```より速い。
+/// 値の出し入れ（```new```と```to_int```）のときだけ通常の表現と変換する。
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MontgomeryModInt<const MOD: u64> {
+    montgomery: u64,
+}
+
+impl<const MOD: u64> MontgomeryModInt<MOD> {
+    /// -MOD^(-1) mod 2^64 。Newton法で MOD^(-1) mod 2^64 を求める。
+    const NEG_INV: u64 = {
+        assert!(
+            MOD % 2 == 1,
+            "Montgomery表現の法は奇数である必要があります。"
+        );
+        let mut inv: u64 = 1;
+        let mut i = 0;
+        while i < 6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(MOD.wrapping_mul(inv)));
+            i += 1;
+        }
+        inv.wrapping_neg()
+    };
+
+    /// R^2 mod MOD
+    const R2: u64 = {
+        let r = (u64::MAX % MOD + 1) as u128 % MOD as u128;
+        (r * r % MOD as u128) as u64
+    };
+
+    /// Montgomery reduction 。t < MOD R に対して t R^(-1) mod MOD を返す。
+    fn reduce(t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(Self::NEG_INV);
+        let mn = m as u128 * MOD as u128;
+        // t + mN の下位64ビットは0になるので、繰り上がりだけを見る
+        let carry = (t as u64).overflowing_add(mn as u64).1 as u128;
+        let u = (t >> 64) + (mn >> 64) + carry;
+        if u >= MOD as u128 {
+            (u - MOD as u128) as u64
+        } else {
+            u as u64
+        }
+    }
+
+    /// コンストラクタ。nをMontgomery表現に変換して格納。
+    pub fn new(n: u64) -> Self {
+        Self {
+            montgomery: Self::reduce((n % MOD) as u128 * Self::R2 as u128),
+        }
+    }
+
+    /// ```u64```型にする（通常の表現に戻す）。
+    pub fn to_int(&self) -> u64 {
+        Self::reduce(self.montgomery as u128)
+    }
+
+    /// 繰り返し二乗法によるべき乗の計算
+    pub fn modpow(&self, n: u64) -> Self {
+        let mut res = Self::identity();
+        let mut a = *self;
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res *= a;
+            }
+            a *= a;
+            m /= 2;
+        }
+        res
+    }
+
+    /// 通常の表現の```ModInt```にする。
+    pub fn to_modint(&self) -> ModInt<MOD> {
+        ModInt::new(self.to_int())
+    }
+
+    /// 通常の表現の```ModInt```から作る。
+    pub fn from_modint(x: ModInt<MOD>) -> Self {
+        Self::new(x.to_int())
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<const MOD: u64> fmt::Display for MontgomeryModInt<MOD> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_int())
+    }
+}
+
+/// 足し算と引き算はMontgomery表現のままでよい。
+impl<const MOD: u64> Add for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            montgomery: add_mod::<MOD>(self.montgomery, rhs.montgomery),
+        }
+    }
+}
+
+impl<const MOD: u64> AddAssign for MontgomeryModInt<MOD> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const MOD: u64> Sub for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            montgomery: sub_mod::<MOD>(self.montgomery, rhs.montgomery),
+        }
+    }
+}
+
+impl<const MOD: u64> SubAssign for MontgomeryModInt<MOD> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+/// (aR)(bR)R^(-1) = abR なので、積をREDCするだけでよい。
+impl<const MOD: u64> Mul for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self {
+            montgomery: Self::reduce(self.montgomery as u128 * rhs.montgomery as u128),
+        }
+    }
+}
+
+impl<const MOD: u64> MulAssign for MontgomeryModInt<MOD> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// 逆元を掛ける。逆元が存在しない元（0を含む）で割るとpanicする。
+impl<const MOD: u64> Div for MontgomeryModInt<MOD> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs
+            .inverse()
+            .expect("逆元が存在しない元で割ることはできません。")
+    }
+}
+
+impl<const MOD: u64> DivAssign for MontgomeryModInt<MOD> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+/// 割り算。逆元が存在しない元（0を含む）で割る場合は```None```を返す。
+impl<const MOD: u64> CheckedDiv for MontgomeryModInt<MOD> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        Some(*self * v.inverse()?)
+    }
+}
+
+/// unary negation の実装
+impl<const MOD: u64> Neg for MontgomeryModInt<MOD> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::zero() - self
+    }
+}
+
+impl<const MOD: u64> Zero for MontgomeryModInt<MOD> {
+    fn zero() -> Self {
+        Self { montgomery: 0 }
+    }
+}
+
+impl<const MOD: u64> Identity for MontgomeryModInt<MOD> {
+    fn identity() -> Self {
+        Self::new(1)
+    }
+}
+
+// 以下は頻繁に呼ばれないので、通常の表現の```ModInt```に変換して計算する。
+
+impl<const MOD: u64> Inverse for MontgomeryModInt<MOD> {
+    fn inverse(self) -> Option<Self> {
+        self.to_modint().inverse().map(Self::from_modint)
+    }
+}
+
+impl<const MOD: u64> SquareRoot for MontgomeryModInt<MOD> {
+    fn square_root(self) -> Option<Self> {
+        self.to_modint().square_root().map(Self::from_modint)
+    }
+}
+
+impl<const MOD: u64> Characteristic for MontgomeryModInt<MOD> {
+    fn characteristic() -> BigUint {
+        BigUint::from(MOD)
+    }
+}
+
+impl<const MOD: u64> Order for MontgomeryModInt<MOD> {
+    fn order() -> u64 {
+        MOD
+    }
+}
+
+impl<const MOD: u64> Elements for MontgomeryModInt<MOD> {
+    fn elements() -> Vec<Self> {
+        (0..MOD).map(Self::new).collect()
+    }
+}
+
+impl<const MOD: u64> ToInteger for MontgomeryModInt<MOD> {
+    fn to_integer(&self) -> u64 {
+        self.to_int()
+    }
+}
+
+impl<const MOD: u64> FromInteger for MontgomeryModInt<MOD> {
+    fn from_integer(n: u64) -> Option<Self> {
+        ModInt::<MOD>::from_integer(n).map(Self::from_modint)
+    }
+}
+
+impl<const MOD: u64> Sign for MontgomeryModInt<MOD> {
+    fn sign(&self) -> bool {
+        self.to_modint().sign()
+    }
+}
+
+impl<const MOD: u64> ByteEncoding for MontgomeryModInt<MOD> {
+    fn byte_length() -> usize {
+        ModInt::<MOD>::byte_length()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_modint().to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        ModInt::<MOD>::from_bytes(bytes).map(Self::from_modint)
+    }
+}
+
+impl<const MOD: u64> FiniteField for MontgomeryModInt<MOD> {
+    fn degree() -> u32 {
+        1
+    }
+
+    fn frobenius(self) -> Self {
+        self
+    }
+}
+
+impl<const MOD: u64> Random for MontgomeryModInt<MOD> {
+    fn random(rng: &mut Rng) -> Self {
+        Self::from_modint(ModInt::random(rng))
+    }
+}
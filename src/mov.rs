@@ -0,0 +1,253 @@
+use crate::arithmetic::power;
+use crate::discrete_log::field_baby_step_giant_step;
+use crate::elements::{Elements, Order};
+use crate::elliptic_curve::EllipticCurve;
+use crate::extension_field::ExtensionField;
+use crate::finite_field::FiniteField;
+use crate::identities::{Identity, Zero};
+use crate::number_theory::factorize;
+use crate::pairing::PairingError;
+use crate::polynomial::Polynomial;
+use crate::random::{Random, Rng};
+use crate::rational_point::RationalPoint;
+use crate::ring::Ring;
+use crate::square_root::SquareRoot;
+use std::fmt;
+use std::hash::Hash;
+
+/// MOV攻撃（Frey–Rück攻撃）が失敗したときのエラー
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MovError {
+    /// ペアリングが計算できない
+    Pairing(PairingError),
+    /// Pとのペアリングが位数nになる点が見つからない
+    Degenerate,
+    /// QがPの生成する部分群に入っていない
+    NoSolution,
+    /// x^k - c の形の既約多項式がないか、位数 q^k が```u64```に収まらないので、F_q^k を作れない
+    Extension,
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for MovError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MovError::Pairing(e) => write!(f, "{}", e),
+            MovError::Degenerate => {
+                write!(f, "ペアリングの値が1の原始n乗根になる点が見つかりません。")
+            }
+            MovError::NoSolution => write!(f, "QはPの生成する部分群に入っていません。"),
+            MovError::Extension => write!(f, "F_q^k を作れません。"),
+        }
+    }
+}
+
+impl std::error::Error for MovError {}
+
+impl From<PairingError> for MovError {
+    fn from(e: PairingError) -> Self {
+        MovError::Pairing(e)
+    }
+}
+
+/// MOV攻撃で楕円曲線上の離散対数問題 Q = [k]P を有限体の離散対数問題 β = α^k に帰着した結果。
+///
+/// 埋め込み次数をdとすると、ペアリングの値は F_q^d = F_q\[x\]/(x^d - c) に入るので、
+/// 次数がd未満のxの多項式で表して持つ（d = 1 なら定数）。補助の点Rの座標も同様。
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct MovReduction<T> {
+    embedding_degree: u64,
+    auxiliary_point: RationalPoint<Polynomial<T>>,
+    alpha: Polynomial<T>,
+    beta: Polynomial<T>,
+    log: u64,
+}
+
+impl<T: Clone> MovReduction<T> {
+    /// 埋め込み次数d
+    pub fn embedding_degree(&self) -> u64 {
+        self.embedding_degree
+    }
+
+    /// ペアリングの相手に使った F_q^d 上の点R
+    pub fn auxiliary_point(&self) -> RationalPoint<Polynomial<T>> {
+        self.auxiliary_point.clone()
+    }
+
+    /// α = e(P, R)
+    pub fn alpha(&self) -> Polynomial<T> {
+        self.alpha.clone()
+    }
+
+    /// β = e(Q, R)
+    pub fn beta(&self) -> Polynomial<T> {
+        self.beta.clone()
+    }
+
+    /// 求まった離散対数 k
+    pub fn log(&self) -> u64 {
+        self.log
+    }
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl<T: fmt::Display + Zero + Identity + Eq> fmt::Display for MovReduction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "R = {}, α = {}, β = {}, β = α^{}",
+            self.auxiliary_point, self.alpha, self.beta, self.log
+        )
+    }
+}
+
+/// 埋め込み次数が2以上のとき、ペアリングが1の原始n乗根になる点Rを探す回数の上限
+pub const AUXILIARY_POINT_ATTEMPTS: u32 = 100;
+
+/// xの乗法的位数がちょうどnかどうか。
+fn has_order<T: Ring>(x: T, n: u64) -> bool {
+    power(x, n) == T::identity()
+        && factorize(n)
+            .iter()
+            .all(|&(l, _)| power(x, n / l) != T::identity())
+}
+
+/// 点の座標を写す。
+fn map_point<T: Copy, U>(point: &RationalPoint<T>, f: impl Fn(T) -> U) -> RationalPoint<U> {
+    match *point {
+        RationalPoint::O => RationalPoint::O,
+        RationalPoint::Point(x, y) => RationalPoint::Point(f(x), f(y)),
+    }
+}
+
+impl<T: FiniteField + Hash + Order + Elements + 'static> EllipticCurve<T> {
+    /// MOV攻撃。位数nの点Pと Q = [k]P から、ペアリングで
+    /// α = e(P, R), β = e(Q, R) = α^k を作り、有限体の乗法群でのBaby-step giant-step法で k を求める。
+    ///
+    /// 埋め込み次数dが1なら値は F_q に入る。E[n] が全て有理的ならWeilペアリングを、
+    /// そうでなければ被約Tateペアリングを使う（Frey–Rück攻撃）。
+    /// dが2, 3, 4, 6のときは曲線を F_q^d (```ExtensionField```) 上に持ち上げ、
+    /// F_q^d 上の点Rとの被約Tateペアリングを F_q^d の中で計算する。
+    /// 超特異曲線の埋め込み次数は6以下なので、F_p や F_p^2 上の超特異曲線はこれで扱える。
+    /// 最後に [k]P = Q を確かめる。
+    ///
+    /// 曲線上にない点は最初に```PairingError::NotOnCurve```で弾く。
+    /// q と n が互いに素でない場合は```PairingError::NotCoprime```を、
+    /// dがそれ以外の場合は```PairingError::EmbeddingDegree(d)```を返す。
+    pub fn mov_attack(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Result<MovReduction<T>, MovError> {
+        if !self.contains(p) || !self.contains(q) {
+            return Err(PairingError::NotOnCurve.into());
+        }
+        if self.multiply(p, n) != RationalPoint::O {
+            return Err(PairingError::NotTorsion.into());
+        }
+        if self.multiply(q, n) != RationalPoint::O {
+            return Err(MovError::NoSolution);
+        }
+        match self.embedding_degree(n) {
+            Some(1) => self.mov_attack_rational(p, q, n),
+            Some(2) => self.mov_attack_extension::<2>(p, q, n),
+            Some(3) => self.mov_attack_extension::<3>(p, q, n),
+            Some(4) => self.mov_attack_extension::<4>(p, q, n),
+            Some(6) => self.mov_attack_extension::<6>(p, q, n),
+            Some(d) => Err(PairingError::EmbeddingDegree(d).into()),
+            None => Err(PairingError::NotCoprime.into()),
+        }
+    }
+
+    /// 埋め込み次数が1の場合。E(F_q) の点を順に試して、ペアリングが1の原始n乗根になる点Rを探す。
+    fn mov_attack_rational(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Result<MovReduction<T>, MovError> {
+        let points = self.points();
+        let weil = points.iter().find_map(|r| {
+            let alpha = self.weil_pairing(p, r, n).ok()?;
+            if has_order(alpha, n) {
+                Some((*r, alpha, self.weil_pairing(q, r, n)))
+            } else {
+                None
+            }
+        });
+        let (r, alpha, beta) = match weil {
+            Some(found) => found,
+            None => points
+                .iter()
+                .find_map(|r| {
+                    let alpha = self.tate_pairing(p, r, n).ok()?;
+                    if has_order(alpha, n) {
+                        Some((*r, alpha, self.tate_pairing(q, r, n)))
+                    } else {
+                        None
+                    }
+                })
+                .ok_or(MovError::Degenerate)?,
+        };
+        let beta = beta?;
+        let log = field_baby_step_giant_step(alpha, beta, n).ok_or(MovError::NoSolution)?;
+        if self.multiply(p, log) != *q {
+            return Err(MovError::NoSolution);
+        }
+        Ok(MovReduction {
+            embedding_degree: 1,
+            auxiliary_point: map_point(&r, Polynomial::new_constant),
+            alpha: Polynomial::new_constant(alpha),
+            beta: Polynomial::new_constant(beta),
+            log,
+        })
+    }
+
+    /// 埋め込み次数がDの場合。曲線を F_q^D 上に持ち上げると埋め込み次数が1になるので、
+    /// F_q^D 上のランダムな点Rをとり、被約Tateペアリング α = f_{n,P}(R)^((q^D - 1)/n) 、
+    /// β = f_{n,Q}(R)^((q^D - 1)/n) を F_q^D の中で計算する。
+    /// αが1の原始n乗根になるRが```AUXILIARY_POINT_ATTEMPTS```回以内に見つからなければ```MovError::Degenerate```を返す。
+    fn mov_attack_extension<const D: usize>(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Result<MovReduction<T>, MovError> {
+        ExtensionField::<T, D>::modulus().ok_or(MovError::Extension)?;
+        let exponent = T::order()
+            .checked_pow(D as u32)
+            .ok_or(MovError::Extension)?
+            / n;
+        let embed = ExtensionField::<T, D>::embed;
+        let lifted = EllipticCurve::new(embed(self.a()), embed(self.b()));
+        let (p_lifted, q_lifted) = (map_point(p, embed), map_point(q, embed));
+        // 結果を再現できるようにシードは固定する
+        let mut rng = Rng::new(0);
+        for _ in 0..AUXILIARY_POINT_ATTEMPTS {
+            let x = ExtensionField::<T, D>::random(&mut rng);
+            let y = match (x * x * x + lifted.a() * x + lifted.b()).square_root() {
+                Some(y) => y,
+                None => continue,
+            };
+            let r = RationalPoint::Point(x, y);
+            let pairing = |point| lifted.miller(point, &r, n).map(|f| power(f, exponent));
+            let (alpha, beta) = match (pairing(&p_lifted), pairing(&q_lifted)) {
+                (Some(alpha), Some(beta)) if has_order(alpha, n) => (alpha, beta),
+                _ => continue,
+            };
+            let log = field_baby_step_giant_step(alpha, beta, n).ok_or(MovError::NoSolution)?;
+            if self.multiply(p, log) != *q {
+                return Err(MovError::NoSolution);
+            }
+            return Ok(MovReduction {
+                embedding_degree: D as u64,
+                auxiliary_point: map_point(&r, |t| t.to_polynomial()),
+                alpha: alpha.to_polynomial(),
+                beta: beta.to_polynomial(),
+                log,
+            });
+        }
+        Err(MovError::Degenerate)
+    }
+}
@@ -0,0 +1,305 @@
+use crate::random::Rng;
+use num::{BigUint, Integer, One, ToPrimitive, Zero};
+
+/// 試し割りによる素因数分解。(素因数, 指数) を素因数の小さい順に並べて返す。
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    let mut res: Vec<(u64, u32)> = Vec::new();
+    let mut m = n;
+    let mut d = 2;
+    // d * d は m が 2^64 に近いとオーバーフローするので、割り算で比べる
+    while d <= m / d {
+        if m.is_multiple_of(d) {
+            let mut e = 0;
+            while m.is_multiple_of(d) {
+                m /= d;
+                e += 1;
+            }
+            res.push((d, e));
+        }
+        d += 1;
+    }
+    if m > 1 {
+        res.push((m, 1));
+    }
+    res
+}
+
+/// 最小公倍数
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a.lcm(&b)
+}
+
+/// 埋め込み次数。r | q^k - 1 となる最小の k 、すなわち q の mod r での位数。
+/// q と r が互いに素でない場合は```None```を返す。
+pub fn embedding_degree(q: u64, r: u64) -> Option<u64> {
+    if r == 1 {
+        return Some(1);
+    }
+    if q.gcd(&r) != 1 {
+        return None;
+    }
+    let q = (q % r) as u128;
+    let mut x = q;
+    let mut k = 1;
+    while x != 1 {
+        x = x * q % r as u128;
+        k += 1;
+    }
+    Some(k)
+}
+
+/// a * b mod n を、オーバーフローしないように u128 で計算する。
+pub const fn mul_mod(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 * b as u128) % n as u128) as u64
+}
+
+/// 拡張ユークリッドの互除法による mod n での逆元。gcd(a, n) ≠ 1 のときは```None```を返す。
+pub fn mod_inverse(a: u64, n: u64) -> Option<u64> {
+    let (mut old_r, mut r) = (a as i128 % n as i128, n as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(n as i128) as u64)
+}
+
+/// 中国剰余定理。x ≡ r_i (mod m_i) を満たす x を mod Πm_i で返す。m_i は互いに素であるとする。
+pub fn crt(congruences: &[(u64, u64)]) -> (u64, u64) {
+    let mut x = 0;
+    let mut m = 1;
+    for &(r, n) in congruences {
+        // x + m t ≡ r (mod n) となる t を求める
+        let t = mul_mod((r % n + n - x % n) % n, mod_inverse(m % n, n).unwrap(), n);
+        let new_m = m * n;
+        x = ((x as u128 + m as u128 * t as u128) % new_m as u128) as u64;
+        m = new_m;
+    }
+    (x, m)
+}
+
+/// 繰り返し二乗法による a^e mod n
+const fn pow_mod(a: u64, e: u64, n: u64) -> u64 {
+    let mut res = 1 % n;
+    let mut a = a % n;
+    let mut e = e;
+    while e > 0 {
+        if e % 2 == 1 {
+            res = mul_mod(res, a, n);
+        }
+        a = mul_mod(a, a, n);
+        e /= 2;
+    }
+    res
+}
+
+/// Miller–Rabin法の底。最初の12個の素数を使えば 2^64 未満の整数は決定的に判定できる。
+const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// 確率的素数判定で```BigUint```に対して繰り返す回数。合成数を素数と誤る確率は 4^(-40) 以下。
+pub const MILLER_RABIN_ROUNDS: u32 = 40;
+
+/// 決定的Miller–Rabin法による素数判定。```const fn```なので定数の計算にも使える。
+pub const fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let mut i = 0;
+    while i < BASES.len() {
+        if n.is_multiple_of(BASES[i]) {
+            return n == BASES[i];
+        }
+        i += 1;
+    }
+    // n - 1 = d 2^s (dは奇数)
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut i = 0;
+    'bases: while i < BASES.len() {
+        let mut x = pow_mod(BASES[i], d, n);
+        i += 1;
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        let mut r = 1;
+        while r < s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+            r += 1;
+        }
+        return false;
+    }
+    true
+}
+
+/// 0 以上 n 未満の一様な多倍長整数。n のビット長だけ乱数をとり、n 以上なら取り直す。
+pub(crate) fn random_below(n: &BigUint, rng: &mut Rng) -> BigUint {
+    let bits = n.bits();
+    loop {
+        let mut digits: Vec<u64> = (0..bits.div_ceil(64)).map(|_| rng.next_u64()).collect();
+        if !bits.is_multiple_of(64) {
+            let last = digits.len() - 1;
+            digits[last] &= (1 << (bits % 64)) - 1;
+        }
+        let x = digits
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |x, &digit| (x << 64) + digit);
+        if &x < n {
+            return x;
+        }
+    }
+}
+
+/// 多倍長整数の確率的素数判定。底を乱数で選ぶMiller–Rabin法を```rounds```回繰り返す。
+/// 素数なら必ず```true```を返し、合成数を```true```と誤る確率は 4^(-rounds) 以下。
+/// ```u64```に収まる場合は```is_prime```で決定的に判定する。
+pub fn is_probable_prime(n: &BigUint, rounds: u32, rng: &mut Rng) -> bool {
+    if let Some(n) = n.to_u64() {
+        return is_prime(n);
+    }
+    if BASES.iter().any(|&p| (n % p).is_zero()) {
+        return false;
+    }
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+    let two = BigUint::from(2u32);
+    'rounds: for _ in 0..rounds {
+        // 2 ≤ a ≤ n - 2
+        let a = random_below(&(n - 3u32), rng) + &two;
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'rounds;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// n 以上で4で割って3余る最小の素数。ただし p = 3 は除く。
+/// このプログラムで F_p^2 = F_p[i] を作るのに使える p なので、```const P```の計算に使える。
+/// そのような素数が```u64```に収まらない場合はpanicする。
+pub const fn next_prime_3_mod_4(n: u64) -> u64 {
+    let mut p = if n <= 7 { 7 } else { n + (7 - n % 4) % 4 };
+    loop {
+        if is_prime(p) {
+            return p;
+        }
+        p = match p.checked_add(4) {
+            Some(p) => p,
+            None => panic!("条件を満たす素数がu64に収まりません。"),
+        };
+    }
+}
+
+/// 候補がこの個数以下なら、全て調べてから素数を1つ選ぶ。
+const EXHAUSTIVE_LIMIT: u64 = 4096;
+
+/// 剰余類 p ≡ r (mod m) を奇数に制限する。3以上の素数は奇数なので、
+/// mが奇数なら法を2mにして、偶数の候補を初めから引かないようにする。
+fn odd_residue_class(r: u64, m: u64) -> (u128, u128) {
+    let (r, m) = ((r % m) as u128, m as u128);
+    if m % 2 == 0 {
+        (r, m)
+    } else if r % 2 == 1 {
+        (r, 2 * m)
+    } else {
+        (r + m, 2 * m)
+    }
+}
+
+/// ちょうど```bits```ビットで、```residue_class``` = (r, m) について p ≡ r (mod m) となる素数を乱数で選ぶ。
+///
+/// 試すたびに範囲内の剰余類から奇数の候補を一様に引き直すので、素数の間隔によらず各素数が等確率で選ばれる。
+/// 候補が```EXHAUSTIVE_LIMIT```個以下のときは全て調べる。それより多いときは範囲内に素数があるとみなして、
+/// 見つかるまで引き直す。
+/// ```bits```が2以上64以下でない場合、gcd(r, m) ≠ 1 の場合、範囲内に素数がない場合は```None```を返す。
+pub fn random_prime(bits: u32, residue_class: (u64, u64), rng: &mut Rng) -> Option<u64> {
+    let (r, m) = residue_class;
+    if !(2..=64).contains(&bits) || m == 0 || r.gcd(&m) != 1 {
+        return None;
+    }
+    // 2ビットの範囲 {2, 3} には偶数の素数2が入るので、奇数に制限しない
+    let (r, m) = if bits == 2 {
+        ((r % m) as u128, m as u128)
+    } else {
+        odd_residue_class(r, m)
+    };
+    let low = 1u128 << (bits - 1);
+    let high = (1u128 << bits) - 1;
+    // low 以上で剰余類に入る最小の元
+    let first = low + (r + m - low % m) % m;
+    if first > high {
+        return None;
+    }
+    // first ≥ 2^(bits - 1) なので count ≤ 2^(bits - 1) で、```u64```に収まる
+    let count = ((high - first) / m + 1) as u64;
+    let candidate = |i: u64| (first + i as u128 * m) as u64;
+    if count <= EXHAUSTIVE_LIMIT {
+        let primes: Vec<u64> = (0..count).map(candidate).filter(|&p| is_prime(p)).collect();
+        if primes.is_empty() {
+            return None;
+        }
+        return Some(primes[rng.below(primes.len() as u64) as usize]);
+    }
+    loop {
+        let p = candidate(rng.below(count));
+        if is_prime(p) {
+            return Some(p);
+        }
+    }
+}
+
+/// ```random_prime```の多倍長整数版。素数判定は```MILLER_RABIN_ROUNDS```回のMiller–Rabin法で行う。
+/// ```bits```が2未満の場合、gcd(r, m) ≠ 1 の場合、範囲内に素数がない場合は```None```を返す。
+pub fn random_big_prime(bits: u64, residue_class: (u64, u64), rng: &mut Rng) -> Option<BigUint> {
+    if bits <= 64 {
+        return random_prime(bits as u32, residue_class, rng).map(BigUint::from);
+    }
+    let (r, m) = residue_class;
+    if m == 0 || r.gcd(&m) != 1 {
+        return None;
+    }
+    let (r, m) = odd_residue_class(r, m);
+    let low = BigUint::one() << (bits - 1);
+    let high = (BigUint::one() << bits) - 1u32;
+    let first = &low + (r + m - (&low % m).to_u128().unwrap()) % m;
+    let count = (&high - &first) / m + 1u32;
+    let candidate = |i: &BigUint| &first + i * m;
+    if count <= BigUint::from(EXHAUSTIVE_LIMIT) {
+        let mut primes = Vec::new();
+        let mut i = BigUint::zero();
+        while i < count {
+            let p = candidate(&i);
+            if is_probable_prime(&p, MILLER_RABIN_ROUNDS, rng) {
+                primes.push(p);
+            }
+            i += 1u32;
+        }
+        if primes.is_empty() {
+            return None;
+        }
+        let k = rng.below(primes.len() as u64) as usize;
+        return Some(primes.swap_remove(k));
+    }
+    loop {
+        let p = candidate(&random_below(&count, rng));
+        if is_probable_prime(&p, MILLER_RABIN_ROUNDS, rng) {
+            return Some(p);
+        }
+    }
+}
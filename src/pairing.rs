@@ -0,0 +1,186 @@
+use crate::arithmetic::power;
+use crate::elements::{Elements, Order};
+use crate::elliptic_curve::EllipticCurve;
+use crate::field::Field;
+use crate::number_theory::embedding_degree;
+use crate::rational_point::RationalPoint;
+use std::fmt;
+
+/// ペアリングを計算できないときのエラー
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PairingError {
+    /// 点が曲線上にない
+    NotOnCurve,
+    /// 点がn等分点（[n]P = O となる点）ではない
+    NotTorsion,
+    /// 埋め込み次数が1ではないので、値が F_q の拡大体に入ってしまう。
+    EmbeddingDegree(u64),
+    /// q と r が互いに素でないので、埋め込み次数が定まらない
+    NotCoprime,
+    /// 零点や極を避けるための補助の点が F_q 上に見つからない
+    NoAuxiliaryPoint,
+}
+
+/// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
+impl fmt::Display for PairingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PairingError::NotOnCurve => write!(f, "点が曲線上にありません。"),
+            PairingError::NotTorsion => write!(f, "点がn等分点ではありません。"),
+            PairingError::EmbeddingDegree(k) => {
+                write!(f, "埋め込み次数が{}なので、F_q 上では計算できません。", k)
+            }
+            PairingError::NotCoprime => {
+                write!(f, "qとrが互いに素でないので、埋め込み次数が定まりません。")
+            }
+            PairingError::NoAuxiliaryPoint => write!(f, "補助の点が見つかりません。"),
+        }
+    }
+}
+
+impl std::error::Error for PairingError {}
+
+impl<T: Field> EllipticCurve<T> {
+    /// 直線 l_{A,B} を縦線 v_{A+B} で割った関数の点Qでの値。
+    /// 零点や極にぶつかった場合は```None```を返す。
+    fn line_over_vertical(
+        &self,
+        a: &RationalPoint<T>,
+        b: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+    ) -> Option<T> {
+        let (xq, yq) = match *q {
+            RationalPoint::O => return None,
+            RationalPoint::Point(x, y) => (x, y),
+        };
+        match (*a, *b) {
+            (RationalPoint::Point(x1, y1), RationalPoint::Point(x2, y2)) => {
+                if x1 == x2 && (y1 != y2 || y1 == T::zero()) {
+                    // A = -B なので縦線 x - x1 、A + B = O なので分母は1
+                    let l = xq - x1;
+                    return if l == T::zero() { None } else { Some(l) };
+                }
+                let id = T::identity();
+                let lambda = if x1 == x2 {
+                    ((id + id + id) * x1 * x1 + self.a()).checked_div(&((id + id) * y1))?
+                } else {
+                    (y2 - y1).checked_div(&(x2 - x1))?
+                };
+                let x3 = lambda * lambda - x1 - x2;
+                let l = yq - y1 - lambda * (xq - x1);
+                let v = xq - x3;
+                if l == T::zero() {
+                    None
+                } else {
+                    l.checked_div(&v)
+                }
+            }
+            _ => Some(T::identity()),
+        }
+    }
+
+    /// Millerのアルゴリズムで、因子 n(P) - n(O) をもつ（正規化された）関数 f_{n,P} の点Qでの値を求める。
+    /// 途中で零点や極にぶつかった場合（QがPの倍数のとき）は```None```を返す。
+    pub fn miller(&self, p: &RationalPoint<T>, q: &RationalPoint<T>, n: u64) -> Option<T> {
+        let mut f = T::identity();
+        let mut t = *p;
+        if n == 0 {
+            return Some(f);
+        }
+        let bits = 64 - n.leading_zeros();
+        for i in (0..(bits - 1)).rev() {
+            f = f * f * self.line_over_vertical(&t, &t, q)?;
+            t = self.add(&t, &t);
+            if (n >> i) & 1 == 1 {
+                f *= self.line_over_vertical(&t, p, q)?;
+                t = self.add(&t, p);
+            }
+        }
+        Some(f)
+    }
+
+    /// Weilペアリング e_n(P, Q) = (-1)^n f_{n,P}(Q) / f_{n,Q}(P) 。
+    /// P, Q は曲線上のn等分点である必要がある。
+    /// Millerのアルゴリズムが零点にぶつかるのは P, Q が一次従属のときなので、そのときは1を返す。
+    pub fn weil_pairing(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        n: u64,
+    ) -> Result<T, PairingError> {
+        for point in [p, q] {
+            if !self.contains(point) {
+                return Err(PairingError::NotOnCurve);
+            }
+            if self.multiply(point, n) != RationalPoint::O {
+                return Err(PairingError::NotTorsion);
+            }
+        }
+        if *p == RationalPoint::O || *q == RationalPoint::O || p == q {
+            return Ok(T::identity());
+        }
+        let numerator = self.miller(p, q, n);
+        let denominator = self.miller(q, p, n);
+        match (numerator, denominator) {
+            (Some(u), Some(v)) => {
+                let e = u / v;
+                Ok(if n % 2 == 1 { -e } else { e })
+            }
+            _ => Ok(T::identity()),
+        }
+    }
+}
+
+impl<T: Field + Order + Elements> EllipticCurve<T> {
+    /// 位数rの部分群の埋め込み次数。r | q^k - 1 となる最小の k 。
+    pub fn embedding_degree(&self, r: u64) -> Option<u64> {
+        embedding_degree(T::order(), r)
+    }
+
+    /// 被約Tateペアリング t_r(P, Q) = f_{r,P}(Q)^((q - 1)/r) 。
+    /// Pはr等分点、Qは任意の有理点で、値は1のr乗根になる。
+    ///
+    /// 値が F_q に入るように、埋め込み次数が1 (r | q - 1) の場合のみ扱う。
+    /// 埋め込み次数kが1でないときは```PairingError::EmbeddingDegree(k)```を返すので、
+    /// 曲線を F_q^k 上に持ち上げてから計算すること（F_q^k 上では埋め込み次数が1になる）。
+    /// q と r が互いに素でない場合は```PairingError::NotCoprime```を返す。
+    ///
+    /// QがPの倍数などで f_{r,P} の零点や極にぶつかるときは、
+    /// 補助の点Sをとって因子 (Q + S) - (S) で評価する。
+    /// E(F_q) が小さすぎてそのようなSがとれないときは```PairingError::NoAuxiliaryPoint```を返す。
+    pub fn tate_pairing(
+        &self,
+        p: &RationalPoint<T>,
+        q: &RationalPoint<T>,
+        r: u64,
+    ) -> Result<T, PairingError> {
+        for point in [p, q] {
+            if !self.contains(point) {
+                return Err(PairingError::NotOnCurve);
+            }
+        }
+        if self.multiply(p, r) != RationalPoint::O {
+            return Err(PairingError::NotTorsion);
+        }
+        let field_order = T::order();
+        match self.embedding_degree(r) {
+            Some(1) => (),
+            Some(k) => return Err(PairingError::EmbeddingDegree(k)),
+            None => return Err(PairingError::NotCoprime),
+        }
+        let exponent = (field_order - 1) / r;
+        if *p == RationalPoint::O || *q == RationalPoint::O {
+            return Ok(T::identity());
+        }
+        if let Some(f) = self.miller(p, q, r) {
+            return Ok(power(f, exponent));
+        }
+        for s in self.points() {
+            let shifted = self.add(q, &s);
+            if let (Some(u), Some(v)) = (self.miller(p, &shifted, r), self.miller(p, &s, r)) {
+                return Ok(power(u / v, exponent));
+            }
+        }
+        Err(PairingError::NoAuxiliaryPoint)
+    }
+}
@@ -1,4 +1,7 @@
+use crate::elements::Elements;
+use crate::field::Field;
 use crate::identities::{Identity, Zero};
+use crate::ring::Ring;
 use std::fmt;
 use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
@@ -27,6 +30,17 @@ impl<T> Polynomial<T> {
     }
 }
 
+impl<T: Zero + Copy> Polynomial<T> {
+    /// i次の項の係数を返す。次数より大きいiに対しては0を返す。
+    pub fn coefficient(&self, i: usize) -> T {
+        if i <= self.degree {
+            self.coefficients[i]
+        } else {
+            T::zero()
+        }
+    }
+}
+
 impl<T: Zero + Eq> Polynomial<T> {
     /// 多項式の次数を返す。こっちは0の次数はNoneにする。
     pub fn strict_deg(&self) -> Option<usize> {
@@ -41,7 +55,7 @@ impl<T: Zero + Eq> Polynomial<T> {
 impl<T: Zero + Eq + Copy> Polynomial<T> {
     /// ```T```型のデータからなるベクトルを受け取り、それを係数としてもつ多項式を作る。
     /// 最高次係数がnon-zeroになるようにするのでTには```Zero```や```Eq```を要求。
-    pub fn new(v: &Vec<T>) -> Self {
+    pub fn new(v: &[T]) -> Self {
         let mut f: Vec<T> = Vec::new();
         if v.len() == 0 {
             f.push(T::zero());
@@ -72,7 +86,7 @@ impl<T: Zero + Eq + Copy> Polynomial<T> {
     }
 }
 
-impl<T: Zero + Identity + Mul<Output = T> + AddAssign + Copy + Eq> Polynomial<T> {
+impl<T: Ring> Polynomial<T> {
     /// 微分（derivative）を求める関数。
     pub fn derivative(f: &Self) -> Self {
         let mut integer = T::identity(); // 1,2,3,...に相当する元を作るために用意
@@ -83,9 +97,7 @@ impl<T: Zero + Identity + Mul<Output = T> + AddAssign + Copy + Eq> Polynomial<T>
         }
         Polynomial::new(&v)
     }
-}
 
-impl<T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq> Polynomial<T> {
     /// 多項式に代入する。
     pub fn evaluate(f: &Self, t: T) -> T {
         let mut t_pow = T::identity();
@@ -98,6 +110,69 @@ impl<T: Zero + Identity + Mul<Output = T> + MulAssign + AddAssign + Copy + Eq> P
     }
 }
 
+impl<T: Field> Polynomial<T> {
+    /// 最高次係数で割ってモニックにする。0はそのまま返す。
+    pub fn monic(f: &Self) -> Self {
+        match f.coefficients[f.degree].inverse() {
+            None => f.clone(),
+            Some(inv) => {
+                let v: Vec<T> = f.coefficients.iter().map(|&c| c * inv).collect();
+                Polynomial::new(&v)
+            }
+        }
+    }
+
+    /// 割り算。(商, 余り) を返す。gが0のときはpanicする。
+    pub fn div_rem(f: &Self, g: &Self) -> (Self, Self) {
+        let lead_inv = g.coefficients[g.degree]
+            .inverse()
+            .expect("0で割ることはできません。");
+        if f.degree < g.degree {
+            return (Polynomial::zero(), f.clone());
+        }
+        let mut r = f.coefficients.clone();
+        let mut q = vec![T::zero(); f.degree - g.degree + 1];
+        for i in (0..=(f.degree - g.degree)).rev() {
+            let c = r[i + g.degree] * lead_inv;
+            q[i] = c;
+            for (j, &d) in g.coefficients.iter().enumerate() {
+                r[i + j] -= c * d;
+            }
+        }
+        (Polynomial::new(&q), Polynomial::new(&r))
+    }
+
+    /// 最大公約数。モニックにして返す。
+    pub fn gcd(f: &Self, g: &Self) -> Self {
+        let mut a = f.clone();
+        let mut b = g.clone();
+        while b.strict_deg().is_some() {
+            let (_, r) = Polynomial::div_rem(&a, &b);
+            a = b;
+            b = r;
+        }
+        Polynomial::monic(&a)
+    }
+}
+
+impl<T: Field + Elements> Polynomial<T> {
+    /// 有限体上の多項式の根を重複度込みで全探索により求める。0の場合は空にする。
+    pub fn roots(f: &Self) -> Vec<T> {
+        let mut res: Vec<T> = Vec::new();
+        if f.strict_deg().is_none() {
+            return res;
+        }
+        let mut g = f.clone();
+        for t in T::elements() {
+            while g.degree > 0 && Polynomial::evaluate(&g, t) == T::zero() {
+                g = Polynomial::div_rem(&g, &Polynomial::new(&[-t, T::identity()])).0;
+                res.push(t);
+            }
+        }
+        res
+    }
+}
+
 /// ```println!```などで見やすく表示させるため、```Display```トレイトを実装。
 impl<T: fmt::Display + Zero + Identity + Eq> fmt::Display for Polynomial<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -338,12 +413,10 @@ impl<T: Copy + Sub<Output = T> + Zero + Eq> SubAssign for Polynomial<T> {
 impl<T: Copy + Add<Output = T> + AddAssign<T> + Mul<Output = T> + Zero + Eq> Mul for Polynomial<T> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self {
-        let mut v: Vec<T> = vec![T::zero(); self.degree * rhs.degree + 1];
-        for i in 0..=(self.degree * rhs.degree) {
-            for j in 0..=i {
-                if i - j <= rhs.degree && j <= self.degree {
-                    v[i] += self.coefficients[j] * rhs.coefficients[i - j];
-                }
+        let mut v: Vec<T> = vec![T::zero(); self.degree + rhs.degree + 1];
+        for (i, &c) in self.coefficients.iter().enumerate() {
+            for (j, &d) in rhs.coefficients.iter().enumerate() {
+                v[i + j] += c * d;
             }
         }
         Polynomial::new(&v)
@@ -354,12 +427,10 @@ impl<T: Copy + Add<Output = T> + AddAssign<T> + Mul<Output = T> + Zero + Eq> Mul
     for Polynomial<T>
 {
     fn mul_assign(&mut self, rhs: Self) {
-        let mut v: Vec<T> = vec![T::zero(); self.degree * rhs.degree + 1];
-        for i in 0..=(self.degree * rhs.degree) {
-            for j in 0..=i {
-                if i - j <= rhs.degree && j <= self.degree {
-                    v[i] += self.coefficients[j] * rhs.coefficients[i - j];
-                }
+        let mut v: Vec<T> = vec![T::zero(); self.degree + rhs.degree + 1];
+        for (i, &c) in self.coefficients.iter().enumerate() {
+            for (j, &d) in rhs.coefficients.iter().enumerate() {
+                v[i + j] += c * d;
             }
         }
         *self = Polynomial::new(&v)
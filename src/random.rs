@@ -0,0 +1,109 @@
+use crate::elliptic_curve::EllipticCurve;
+use crate::finite_field::FiniteField;
+use crate::number_theory::random_below;
+use crate::rational_point::RationalPoint;
+use crate::sign::Sign;
+use num::Zero;
+
+/// シードから決まる擬似乱数生成器（xoshiro256**）。
+///
+/// 同じシードからは常に同じ列が出るので、テストや統計実験を再現できる。暗号用途には使えない。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    /// コンストラクタ。内部状態はシードからsplitmix64で作る。
+    pub fn new(seed: u64) -> Self {
+        let mut s = seed;
+        let mut next = || {
+            s = s.wrapping_add(0x9e3779b97f4a7c15);
+            let mut z = s;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    /// 一様な```u64```
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// 0 以上 n 未満の一様な整数。偏りが出ないように棄却法を使う。n = 0 の場合はpanicする。
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "範囲が空です。");
+        let zone = u64::MAX - (u64::MAX - n + 1) % n;
+        loop {
+            let x = self.next_u64();
+            if x <= zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// 確率1/2の```bool```
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+}
+
+/// 一様な乱数で元を作る。
+pub trait Random {
+    fn random(rng: &mut Rng) -> Self;
+}
+
+impl<T: FiniteField> EllipticCurve<T> {
+    /// 一様な非特異曲線。(a, b) を一様にとり、判別式が0なら取り直す。
+    pub fn random(rng: &mut Rng) -> Self {
+        loop {
+            let curve = EllipticCurve::new(T::random(rng), T::random(rng));
+            if !curve.is_singular() {
+                return curve;
+            }
+        }
+    }
+}
+
+impl<T: FiniteField + Sign> EllipticCurve<T> {
+    /// E(F_q) の一様な点（O を含む）。点を列挙せずに棄却法で作る。
+    ///
+    /// 0 以上 2q 以下の整数kを一様にとり、k = 0 なら O 、そうでなければ x と
+    /// yの符号の組（2q 通り）を一様にとったものとみなす。x^3 + ax + b が平方元でない場合と、y = 0 で符号が奇の場合は取り直す。
+    /// これで各点がちょうど1通りのkに対応するので、分布は一様になる。
+    /// qは```FiniteField::cardinality```で求めるので、```u64```に収まらない位数の体でも使える。
+    pub fn random_point(&self, rng: &mut Rng) -> RationalPoint<T> {
+        let n = T::cardinality() * 2u32 + 1u32;
+        loop {
+            if random_below(&n, rng).is_zero() {
+                return RationalPoint::O;
+            }
+            let x = T::random(rng);
+            let sign = rng.next_bool();
+            let y = match (x * x * x + self.a() * x + self.b()).square_root() {
+                Some(y) => y,
+                None => continue,
+            };
+            if y == T::zero() {
+                if sign {
+                    continue;
+                }
+                return RationalPoint::Point(x, y);
+            }
+            return RationalPoint::Point(x, if y.sign() == sign { y } else { -y });
+        }
+    }
+}
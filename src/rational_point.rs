@@ -1,7 +1,6 @@
-use crate::identities::Identity;
-use crate::inverse::Inverse;
+use crate::field::Field;
 use std::fmt;
-use std::ops::{Add, Mul, Neg, Sub};
+use std::ops::{Add, Neg};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum RationalPoint<T> {
@@ -25,33 +24,10 @@ impl<T: fmt::Display> fmt::Display for RationalPoint<T> {
 }
 
 /// 本当は演算子のオーバーロードをしたかったが、係数aをstaticに用意するのが無理だったので断念。これは記録です。
-impl<
-        T: Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Copy + Eq + Inverse + Identity + A,
-    > Add for RationalPoint<T>
-{
+impl<T: Field + A> Add for RationalPoint<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self {
-        match self {
-            RationalPoint::O => rhs,
-            RationalPoint::Point(x1, y1) => match rhs {
-                RationalPoint::O => RationalPoint::Point(x1, y1),
-                RationalPoint::Point(x2, y2) => {
-                    if x1 == x2 {
-                        if y1 != y2 {
-                            RationalPoint::O
-                        } else {
-                            let id = T::identity();
-                            let m = ((id + id + id) * x1 * x1 + T::a())
-                                * ((id + id) * y1).inverse().unwrap();
-                            RationalPoint::Point(m * m - x1 - x1, m * (x1 - m * m + x1 + x1) - y1)
-                        }
-                    } else {
-                        let m = (y2 - y1) * ((x2 - x1).inverse().unwrap());
-                        RationalPoint::Point(m * m - x1 - x2, m * (x1 - m * m + x1 + x2) - y1)
-                    }
-                }
-            },
-        }
+        self.add_rational_points(&rhs, T::a())
     }
 }
 
@@ -65,9 +41,7 @@ impl<T: Neg<Output = T>> Neg for RationalPoint<T> {
     }
 }
 
-impl<T: Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Copy + Eq + Inverse + Identity>
-    RationalPoint<T>
-{
+impl<T: Field> RationalPoint<T> {
     /// 有理点の足し算。aは y^2 = x^3 + ax + b の a。
     pub fn add_rational_points(&self, rhs: &Self, a: T) -> Self {
         match *self {
@@ -76,22 +50,37 @@ impl<T: Add<Output = T> + Mul<Output = T> + Sub<Output = T> + Copy + Eq + Invers
                 RationalPoint::O => RationalPoint::Point(x1, y1),
                 RationalPoint::Point(x2, y2) => {
                     if x1 == x2 {
-                        if y1 != y2 {
+                        // y = 0 の点は位数2なので 2P = O
+                        if y1 != y2 || y1 == T::zero() {
                             RationalPoint::O
                         } else {
                             let id = T::identity();
-                            let m = ((id + id + id) * x1 * x1 + a)
-                                * ((id + id) * y1).inverse().unwrap();
+                            let m = ((id + id + id) * x1 * x1 + a) / ((id + id) * y1);
                             RationalPoint::Point(m * m - x1 - x1, m * (x1 - m * m + x1 + x1) - y1)
                         }
                     } else {
-                        let m = (y2 - y1) * ((x2 - x1).inverse().unwrap());
+                        let m = (y2 - y1) / (x2 - x1);
                         RationalPoint::Point(m * m - x1 - x2, m * (x1 - m * m + x1 + x2) - y1)
                     }
                 }
             },
         }
     }
+
+    /// 有理点のスカラー倍 [n]P を繰り返し二乗法の要領で計算。aは y^2 = x^3 + ax + b の a。
+    pub fn multiply(&self, n: u64, a: T) -> Self {
+        let mut res = RationalPoint::O;
+        let mut q = *self;
+        let mut m = n;
+        while m > 0 {
+            if m % 2 == 1 {
+                res = res.add_rational_points(&q, a);
+            }
+            q = q.add_rational_points(&q, a);
+            m /= 2;
+        }
+        res
+    }
 }
 
 pub trait A {
@@ -0,0 +1,36 @@
+use crate::identities::{Identity, Zero};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// 可換環。足し算・引き算・掛け算と零元・単位元を持ち、値で受け渡しできるもの。
+///
+/// 個別のトレイト境界を並べる代わりに使う。条件を満たす型には自動で実装される。
+pub trait Ring:
+    Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + Copy
+    + Eq
+    + Zero
+    + Identity
+{
+}
+
+impl<
+        T: Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Neg<Output = T>
+            + AddAssign
+            + SubAssign
+            + MulAssign
+            + Copy
+            + Eq
+            + Zero
+            + Identity,
+    > Ring for T
+{
+}
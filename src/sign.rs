@@ -0,0 +1,5 @@
+/// 有限体の元の「符号」を与える。x と -x (x ≠ 0) でちょうど一方だけが```true```になる。
+/// RFC 9380 の sgn0 にならう。
+pub trait Sign {
+    fn sign(&self) -> bool;
+}
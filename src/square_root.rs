@@ -0,0 +1,7 @@
+/// 平方根を与える。平方元の場合は```Some(平方根の1つ)```を返し、そうでない場合は```None```を返す
+pub trait SquareRoot
+where
+    Self: std::marker::Sized,
+{
+    fn square_root(self) -> Option<Self>;
+}